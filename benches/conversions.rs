@@ -0,0 +1,96 @@
+use criterion::{black_box, criterion_group, criterion_main, BenchmarkId, Criterion};
+use std::collections::VecDeque;
+use unit_conversions::Registry;
+
+/// Unit names cannot contain digits, so the index is written in letters instead
+fn name(mut index: usize) -> String {
+    let mut name = String::from("u");
+    loop {
+        name.push((b'a' + (index % 26) as u8) as char);
+        index /= 26;
+        if index == 0 {
+            return name;
+        }
+    }
+}
+
+/// A chain of units where each one is 2 of the next, so the ends are as far apart as possible.<br>
+/// The factor is a power of 2 so that the exact fraction of the answer stays small, and the search is what is measured
+fn chain(length: usize) -> Registry {
+    let mut registry = Registry::new();
+    for i in 0..length {
+        registry.define_unit(&name(i)).unwrap();
+    }
+    for i in 1..length {
+        registry.define_conversion(&format!("1 {} = 2 {}", name(i - 1), name(i))).unwrap();
+    }
+    registry
+}
+
+/// Adds units that are not connected to anything, like the rest of a large registry
+fn with_unrelated_units(mut registry: Registry, count: usize) -> Registry {
+    for i in 0..count {
+        registry.define_unit(&format!("v{}", name(i))).unwrap();
+    }
+    registry
+}
+
+/// The search that every conversion used to run: the graph of every unit is rebuilt, then searched breadth first
+fn old_bfs(registry: &Registry, start: usize, end: usize) -> Option<Vec<usize>> {
+    let graph: Vec<Vec<usize>> = registry.graph().into_iter()
+        .map(|edges| edges.into_iter().map(|edge| edge.node).collect())
+        .collect();
+    let mut parent = vec![usize::MAX; graph.len()];
+    let mut queue = VecDeque::from([start]);
+    parent[start] = start;
+    while let Some(node) = queue.pop_front() {
+        if node == end {
+            let mut path = vec![end];
+            while *path.last().unwrap() != start {
+                path.push(parent[*path.last().unwrap()]);
+            }
+            path.reverse();
+            return Some(path);
+        }
+        for &next in &graph[node] {
+            if parent[next] == usize::MAX {
+                parent[next] = node;
+                queue.push_back(next);
+            }
+        }
+    }
+    None
+}
+
+fn end_to_end(c: &mut Criterion) {
+    let mut group = c.benchmark_group("conversion");
+    for length in [10, 100, 1000] {
+        let mut registry = chain(length);
+        let (first, last) = (name(0), name(length - 1));
+        let (start, end) = (registry.unit_id(&first).unwrap(), registry.unit_id(&last).unwrap());
+        group.bench_with_input(BenchmarkId::new("path search", length), &last, |b, last| {
+            b.iter(|| registry.convert(black_box(1f64), &first, last).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("old bfs", length), &registry, |b, registry| {
+            b.iter(|| old_bfs(registry, black_box(start), end).unwrap())
+        });
+    }
+    group.finish();
+    // the search only covers the component of the starting unit, so unlike the old one it does not slow down as other units are added
+    let mut group = c.benchmark_group("unrelated units");
+    for count in [0, 1000, 10000] {
+        let mut registry = with_unrelated_units(chain(100), count);
+        let (first, last) = (name(0), name(99));
+        let (start, end) = (registry.unit_id(&first).unwrap(), registry.unit_id(&last).unwrap());
+        group.bench_with_input(BenchmarkId::new("path search", count), &last, |b, last| {
+            b.iter(|| registry.convert(black_box(1f64), &first, last).unwrap())
+        });
+        group.bench_with_input(BenchmarkId::new("old bfs", count), &registry, |b, registry| {
+            b.iter(|| old_bfs(registry, black_box(start), end).unwrap())
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, end_to_end);
+criterion_main!(benches);
@@ -1,61 +1,155 @@
-use std::collections::VecDeque;
-
-pub fn bfs(graph: &Vec<Vec<usize>>, start: usize) -> (Vec<usize>, Vec<usize>) {
-    let mut parent = Vec::new();
-    let mut distance = Vec::new();
-    for _ in 0..graph.len() {
-        parent.push(usize::MAX);
-        distance.push(usize::MAX);
-    }
-    distance[start] = 0;
-    let mut q: VecDeque<usize> = VecDeque::new();
-    q.push_back(start);
-    while !q.is_empty() {
-        let node = q.pop_front().expect("Queue must not be empty during while loop");
-        for neighbor in graph[node].clone() {
-            if distance[neighbor] == usize::MAX {
-                parent[neighbor] = node;
-                distance[neighbor] = distance[node] + 1;
-                q.push_back(neighbor);
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, VecDeque};
+
+/// A link to another node, weighted by the relative error that following it adds to a conversion
+#[derive(Clone, Copy, Debug)]
+pub struct Edge {
+    pub node: usize,
+    pub weight: f64,
+}
+
+/// How far a node is from the start of a search.<br>
+/// Paths are compared by the relative error they accumulate, and then by their number of edges
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Cost {
+    pub error: f64,
+    pub hops: usize,
+}
+
+impl Cost {
+    const ZERO: Cost = Cost { error: 0f64, hops: 0 };
+}
+
+impl Eq for Cost { }
+
+impl Ord for Cost {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.error.total_cmp(&other.error).then(self.hops.cmp(&other.hops))
+    }
+}
+
+impl PartialOrd for Cost {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Finds the most precise path from start to every node reachable from it with Dijkstra's algorithm,
+/// so that the cost does not depend on the size of the whole graph.<br>
+/// Returns the parent and the cost of every node that was reached. The parent of start is `usize::MAX`.<br>
+/// The result is deterministic: nodes of equal cost are settled from the lowest id up, and a path is only replaced
+/// by a strictly cheaper one, so among equally cheap paths the one through the earliest settled nodes wins
+pub fn shortest_path_tree(start: usize, neighbors: impl Fn(usize) -> Vec<Edge>) -> HashMap<usize, (usize, Cost)> {
+    let mut tree = HashMap::from([(start, (usize::MAX, Cost::ZERO))]);
+    let mut heap = BinaryHeap::from([Reverse((Cost::ZERO, start))]);
+    while let Some(Reverse((current, node))) = heap.pop() {
+        if tree.get(&node).is_some_and(|(_, best)| *best < current) {
+            continue; // a better path to this node was already found
+        }
+        for edge in neighbors(node) {
+            let next = Cost {
+                error: current.error + edge.weight,
+                hops: current.hops + 1,
+            };
+            if tree.get(&edge.node).is_none_or(|(_, best)| next < *best) {
+                tree.insert(edge.node, (node, next));
+                heap.push(Reverse((next, edge.node)));
             }
         }
     }
-    (parent, distance)
+    tree
 }
 
-/// Finds the shortest path between start and the first end that it connects to.<br>
-/// Returns the path as a vector of the IDs in order.<br>
-/// If no paths exists, returns `None`
-pub fn find_first_shortest_path(graph: &Vec<Vec<usize>>, start: usize, ends: &mut Vec<usize>) -> Option<Vec<usize>> {
-    let (parent, distance) = bfs(&graph, start);
-    for (index, destination) in ends.iter().enumerate() {
-        let destination = *destination;
-        if distance[destination] < usize::MAX {
-            let mut path = Vec::new();
-            let mut current_node = destination;
-            path.push(destination);
-            while parent[current_node] != usize::MAX {
-                current_node = parent[current_node];
-                path.push(current_node);
+/// Walks the parents in a `shortest_path_tree` back from destination to its start.
+/// The destination must be in the tree
+pub fn build_path(tree: &HashMap<usize, (usize, Cost)>, destination: usize) -> Vec<usize> {
+    let mut path = vec![destination];
+    let mut current_node = destination;
+    while let Some(&(parent, _)) = tree.get(&current_node).filter(|(parent, _)| *parent != usize::MAX) {
+        current_node = parent;
+        path.push(current_node);
+    }
+    path.reverse();
+    path
+}
+
+/// Matches as many starts as possible with a different end, where `candidates` lists the index of every end
+/// that each start can be matched with, in order of preference.<br>
+/// Returns the index of the end that each start was matched with, or `None` if it could not be matched
+pub fn find_matching(candidates: &[Vec<usize>], end_count: usize) -> Vec<Option<usize>> {
+    let mut matched_start: Vec<Option<usize>> = vec![None; end_count];
+    for start in 0..candidates.len() {
+        let mut visited = vec![false; end_count];
+        augment(start, candidates, &mut matched_start, &mut visited);
+    }
+    let mut matched_end = vec![None; candidates.len()];
+    for (end, start) in matched_start.iter().enumerate() {
+        if let Some(start) = *start {
+            matched_end[start] = Some(end);
+        }
+    }
+    matched_end
+}
+
+/// Tries to match `start` with one of its candidates, moving the starts that already hold them onto other ends
+fn augment(start: usize, candidates: &[Vec<usize>], matched_start: &mut [Option<usize>], visited: &mut [bool]) -> bool {
+    for &end in &candidates[start] {
+        if visited[end] {
+            continue;
+        }
+        visited[end] = true;
+        if matched_start[end].is_none_or(|other| augment(other, candidates, matched_start, visited)) {
+            matched_start[end] = Some(start);
+            return true;
+        }
+    }
+    false
+}
+
+/// Builds a spanning tree of every connected part of the graph, searching breadth-first from the lowest id in each.<br>
+/// Returns the parent of each node, which is `usize::MAX` for the root of each tree
+pub fn spanning_forest(graph: &[Vec<Edge>]) -> Vec<usize> {
+    let mut parent = vec![usize::MAX; graph.len()];
+    let mut visited = vec![false; graph.len()];
+    for root in 0..graph.len() {
+        if visited[root] {
+            continue;
+        }
+        visited[root] = true;
+        let mut q: VecDeque<usize> = VecDeque::from([root]);
+        while let Some(node) = q.pop_front() {
+            for edge in &graph[node] {
+                if !visited[edge.node] {
+                    visited[edge.node] = true;
+                    parent[edge.node] = node;
+                    q.push_back(edge.node);
+                }
             }
-            debug_assert!(current_node == start);
-            path.reverse();
-            ends.swap_remove(index); // ensure that this destination is not used again
-            return Some(path)
         }
     }
-    None
+    parent
 }
 
-/// This function matches each `start` with each `end`
-/// on a one-to-on basis by using the shortest paths it can.
-pub fn find_paths_between(starts: &Vec<usize>, ends: &Vec<usize>, graph: &Vec<Vec<usize>>) -> Vec<Vec<usize>> {
-    let mut paths = Vec::new();
-    let mut ends = ends.to_owned();
-    for start in starts {
-        if let Some(path) = find_first_shortest_path(graph, *start, &mut ends) {
-            paths.push(path);
+/// Returns the path from start to end along the edges of a `spanning_forest`.<br>
+/// Both nodes must be in the same tree
+pub fn tree_path(parent: &[usize], start: usize, end: usize) -> Vec<usize> {
+    let ancestors = |mut node: usize| {
+        let mut path = vec![node];
+        while parent[node] != usize::MAX {
+            node = parent[node];
+            path.push(node);
         }
+        path
+    };
+    let mut up = ancestors(start);
+    let mut down = ancestors(end);
+    // drop the ancestors that both share, apart from the lowest one
+    while up.len() >= 2 && down.len() >= 2 && up[up.len() - 2] == down[down.len() - 2] {
+        up.pop();
+        down.pop();
     }
-    paths
-}
\ No newline at end of file
+    debug_assert!(up.last() == down.last());
+    down.pop();
+    up.extend(down.into_iter().rev());
+    up
+}
@@ -0,0 +1,110 @@
+use crate::structs::Dimension;
+use serde::Serialize;
+use std::fmt;
+
+/// Everything that can go wrong while defining units or performing a conversion
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ConversionError {
+    /// An alias in an expression does not belong to any unit
+    UnknownUnit { alias: String },
+    /// A symbol in a chemical formula does not belong to any element. `position` counts the characters before it
+    UnknownElement { symbol: String, position: usize },
+    /// No element has been registered with this atomic number
+    UnknownAtomicNumber { atomic_number: usize },
+    /// An element was defined with an atomic number that already belongs to another element
+    DuplicateAtomicNumber { atomic_number: usize, existing: String },
+    /// A dimension names something other than one of the base quantities
+    UnknownQuantity { quantity: String },
+    /// The text after a `^` is not a whole number, or is 0
+    InvalidExponent { exponent: String },
+    /// An exponent, or one that a dimension reaches by multiplying units together, does not fit between -128 and 127
+    ExponentOutOfRange { expression: String },
+    /// The count after an element in a chemical formula is not a valid number. `position` counts the characters before it
+    InvalidSubscript { subscript: String, position: usize },
+    /// A chemical formula could not be parsed. `position` counts the characters before the one where it failed
+    InvalidFormula { formula: String, position: usize, reason: String },
+    /// A list of elements and their amounts, like `C 40.0%, H 6.7%, O 53.3%`, that cannot be understood
+    InvalidComposition { reason: String },
+    /// The mole ratios of a composition are not close enough to whole numbers, even after being multiplied
+    NoWholeNumberRatio { ratios: Vec<f64> },
+    /// The molar mass of a compound is not close enough to a whole multiple of the molar mass of its empirical formula
+    MolarMassNotMultiple { molar_mass: f64, empirical_molar_mass: f64 },
+    /// A chemical reaction, like `C3H8 + O2 -> CO2 + H2O`, that cannot be understood
+    InvalidReaction { reason: String },
+    /// No set of whole coefficients balances a reaction, or more than one independent set does
+    CannotBalance { reaction: String, reason: String },
+    /// A substance that a conversion starts or ends with is not part of the reaction that it uses
+    NotInReaction { substance: String, reaction: String },
+    /// A conversion has no starting or no ending units, given by `side`
+    MissingUnits { side: String },
+    /// A value appears after the `:` that separates the starting units from the ending units
+    MisplacedNumber,
+    /// A `[` that starts a chemical formula is never closed
+    UnclosedBracket,
+    /// A character that cannot appear where it does, like a `;` in the middle of an expression
+    UnexpectedCharacter { character: char },
+    /// The two sides of a conversion measure different quantities, like a length and a time
+    IncompatibleDimensions { from: Dimension, to: Dimension },
+    /// Two units, or a unit and its product, cannot be linked because they measure different quantities
+    ConflictingDimensions { first: String, first_dimension: Dimension, second: String, second_dimension: Dimension },
+    /// The two sides of a conversion cannot be broken down into the same number of units
+    MismatchedArity { from: String, to: String },
+    /// A new conversion disagrees with the conversion that already connects the two units, beyond their relative errors
+    InconsistentConversion { first: String, second: String, given: f64, implied: f64 },
+    /// Some of the starting units, listed in `unmatched`, cannot be converted to an ending unit of their own
+    UnmatchedUnits { unmatched: Vec<String>, from: String, to: String },
+    /// Units are defined as products of each other too many times to be broken down
+    DefinitionTooDeep,
+    /// A unit, conversion or element definition that cannot be understood
+    MalformedDefinition { reason: String },
+    /// A file of definitions could not be read
+    FileRead { path: String, reason: String },
+}
+
+impl ConversionError {
+    pub(crate) fn malformed(reason: impl Into<String>) -> Self {
+        ConversionError::MalformedDefinition { reason: reason.into() }
+    }
+}
+
+impl fmt::Display for ConversionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConversionError::UnknownUnit { alias } => write!(f, "Invalid Conversion: Unit '{alias}' is not registered."),
+            ConversionError::UnknownElement { symbol, position } => write!(f, "Invalid Chemical: '{symbol}' at character {} is not an element", position + 1),
+            ConversionError::UnknownAtomicNumber { atomic_number } => write!(f, "Atomic number {atomic_number} is undefined"),
+            ConversionError::DuplicateAtomicNumber { atomic_number, existing } => write!(f, "Atomic number {atomic_number} already belongs to {existing}"),
+            ConversionError::UnknownQuantity { quantity } => write!(f, "Invalid Dimension: '{quantity}' is not one of {}", Dimension::BASE_QUANTITIES.join(", ")),
+            ConversionError::InvalidExponent { exponent } => write!(f, "Improper use of exponent: '{exponent}' is not a whole number other than 0"),
+            ConversionError::ExponentOutOfRange { expression } => write!(f, "Improper use of exponent: the exponents of '{expression}' must stay between -128 and 127"),
+            ConversionError::InvalidSubscript { subscript, position } => write!(f, "Invalid Chemical: '{subscript}' at character {} is not a valid subscript", position + 1),
+            ConversionError::InvalidFormula { formula, position, reason } => write!(f, "Invalid Chemical: '{formula}' at character {}: {reason}", position + 1),
+            ConversionError::InvalidComposition { reason } => write!(f, "Invalid Composition: {reason}"),
+            ConversionError::NoWholeNumberRatio { ratios } => write!(f, "The mole ratios {} are not close to whole numbers",
+                ratios.iter().map(|ratio| format!("{ratio:.3}")).collect::<Vec<_>>().join(" : ")),
+            ConversionError::MolarMassNotMultiple { molar_mass, empirical_molar_mass } =>
+                write!(f, "A molar mass of {molar_mass} is not a whole multiple of the empirical formula's {empirical_molar_mass:.3}"),
+            ConversionError::InvalidReaction { reason } => write!(f, "Invalid Reaction: {reason}"),
+            ConversionError::CannotBalance { reaction, reason } => write!(f, "Cannot balance {reaction}: {reason}"),
+            ConversionError::NotInReaction { substance, reaction } => write!(f, "{substance} is not part of {reaction}"),
+            ConversionError::MissingUnits { side } => write!(f, "That conversion is impossible! It has no {side} units"),
+            ConversionError::MisplacedNumber => f.write_str("Invalid Conversion: Improper placement of number after the separating ':'"),
+            ConversionError::UnclosedBracket => f.write_str("Opening brace without closing brace!"),
+            ConversionError::UnexpectedCharacter { character } => write!(f, "Invalid Conversion: Unexpected '{character}'"),
+            ConversionError::IncompatibleDimensions { from, to } => write!(f, "Cannot convert {from} to {to}"),
+            ConversionError::ConflictingDimensions { first, first_dimension, second, second_dimension } =>
+                write!(f, "Cannot link {first} ({first_dimension}) to {second} ({second_dimension}) because their dimensions differ"),
+            ConversionError::MismatchedArity { from, to } => write!(f, "Cannot reduce {from} and {to} to the same units"),
+            ConversionError::InconsistentConversion { first, second, given, implied } =>
+                write!(f, "1 {first} = {given} {second} disagrees with the existing conversions, which give {implied} {second}"),
+            ConversionError::UnmatchedUnits { unmatched, from, to } =>
+                write!(f, "Cannot convert {from} to {to}: {} could not be connected to a unit of {to}", unmatched.join(", ")),
+            ConversionError::DefinitionTooDeep => f.write_str("Units are defined in terms of each other too many times to be broken down"),
+            ConversionError::MalformedDefinition { reason } => f.write_str(reason),
+            ConversionError::FileRead { path, reason } => write!(f, "Could not read {path}: {reason}"),
+        }
+    }
+}
+
+impl std::error::Error for ConversionError { }
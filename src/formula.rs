@@ -0,0 +1,270 @@
+use crate::error::ConversionError;
+use crate::parsing::{subscript_number, unsubscript_digit};
+use serde::Serialize;
+
+/// Characters that join the parts of a hydrate, like the `·` in `CuSO₄·5H₂O`
+const HYDRATE_DOTS: [char; 4] = ['·', '•', '.', '*'];
+
+/// A parsed chemical formula, like `Ca(OH)₂`, `CuSO₄·5H₂O` or `SO₄²⁻`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Formula {
+    /// The atomic number of every element and how many atoms of it there are, in the order that each first appears
+    pub elements: Vec<(usize, u32)>,
+    /// The charge of the ion, or 0 for a neutral compound
+    pub charge: i32,
+    /// The formula the way it was written, but with its counts as subscripts and its charge as superscripts, like `Ca(OH)₂` or `SO₄²⁻`
+    pub written: String,
+}
+
+impl Formula {
+    /// Parses a formula with groups in parentheses, hydrates and a charge, like `(NH₄)₃PO₄`, `CuSO4·5H2O` or `SO4^2-`.<br>
+    /// `resolve_element` turns each symbol, like `Cu`, into its atomic number, or `None` if there is no such element
+    pub fn parse(formula: &str, resolve_element: impl Fn(&str) -> Option<usize>) -> Result<Formula, ConversionError> {
+        let formula = formula.trim();
+        let mut parser = Parser {
+            formula,
+            chars: formula.chars().collect(),
+            position: 0,
+            resolve_element,
+            written: String::new(),
+        };
+        parser.formula()
+    }
+
+    /// Writes the charge as superscripts, like `²⁻`, or nothing if the formula is neutral
+    pub fn charge_superscript(&self) -> String {
+        let sign = match self.charge.signum() {
+            0 => return String::new(),
+            1 => '⁺',
+            _ => '⁻',
+        };
+        let magnitude = self.charge.unsigned_abs();
+        let mut superscript: String = match magnitude {
+            1 => String::new(),
+            _ => magnitude.to_string().chars().map(superscript_digit).collect(),
+        };
+        superscript.push(sign);
+        superscript
+    }
+}
+
+/// Reads a formula one character at a time. Each method parses one rule of the grammar:
+/// ```text
+/// formula  = sequence { dot [count] sequence } [charge]
+/// sequence = unit { unit }
+/// unit     = (element | "(" sequence ")") [count]
+/// element  = uppercase { lowercase }
+/// ```
+struct Parser<'a, F> {
+    formula: &'a str,
+    chars: Vec<char>,
+    /// The index of the next character to read
+    position: usize,
+    resolve_element: F,
+    /// What has been parsed so far, with every count as a subscript
+    written: String,
+}
+
+impl<F: Fn(&str) -> Option<usize>> Parser<'_, F> {
+    fn formula(&mut self) -> Result<Formula, ConversionError> {
+        let mut elements = self.sequence()?;
+        while self.peek_past_spaces().is_some_and(|c| HYDRATE_DOTS.contains(&c)) {
+            self.skip_spaces();
+            self.position += 1;
+            self.skip_spaces();
+            self.written.push('·');
+            let coefficient = self.count()?;
+            if let Some(coefficient) = coefficient {
+                self.written.push_str(&coefficient.to_string());
+            }
+            let part = self.sequence()?;
+            let coefficient = coefficient.unwrap_or(1);
+            self.add(&mut elements, &part, coefficient)?;
+        }
+        let charge = self.charge()?;
+        if let Some(c) = self.peek() {
+            return Err(self.error(format!("unexpected '{c}'")));
+        }
+        let mut formula = Formula { elements, charge, written: std::mem::take(&mut self.written) };
+        formula.written.push_str(&formula.charge_superscript());
+        Ok(formula)
+    }
+
+    /// Parses one or more units, like `SO₄` or `(OH)₂`, and adds up the atoms of each element
+    fn sequence(&mut self) -> Result<Vec<(usize, u32)>, ConversionError> {
+        let mut elements = Vec::new();
+        loop {
+            match self.peek() {
+                Some('(') => {
+                    self.position += 1;
+                    self.written.push('(');
+                    let group = self.sequence()?;
+                    if self.peek() != Some(')') {
+                        return Err(self.error("expected ')' to close the group"));
+                    }
+                    self.position += 1;
+                    self.written.push(')');
+                    let count = self.subscript()?;
+                    self.add(&mut elements, &group, count)?;
+                },
+                Some(c) if c.is_ascii_uppercase() => {
+                    let atomic_number = self.element()?;
+                    let count = self.subscript()?;
+                    self.add(&mut elements, &[(atomic_number, 1)], count)?;
+                },
+                _ => break
+            }
+        }
+        if elements.is_empty() {
+            return Err(self.error("expected an element"));
+        }
+        Ok(elements)
+    }
+
+    /// Parses a symbol, like `Cu`, and returns its atomic number
+    fn element(&mut self) -> Result<usize, ConversionError> {
+        let start = self.position;
+        self.position += 1;
+        while self.peek().is_some_and(|c| c.is_ascii_lowercase()) {
+            self.position += 1;
+        }
+        let symbol: String = self.chars[start..self.position].iter().collect();
+        self.written.push_str(&symbol);
+        (self.resolve_element)(&symbol).ok_or(ConversionError::UnknownElement { symbol, position: start })
+    }
+
+    /// Parses the count after an element or a group, which is 1 if there is none, and writes it as a subscript
+    fn subscript(&mut self) -> Result<u32, ConversionError> {
+        let count = self.count()?;
+        if let Some(count) = count {
+            self.written.push_str(&subscript_number(count));
+        }
+        Ok(count.unwrap_or(1))
+    }
+
+    /// Parses a count made of digits, like `12` or `₁₂`. Returns `None` if there is no count here
+    fn count(&mut self) -> Result<Option<u32>, ConversionError> {
+        let start = self.position;
+        let mut digits = String::new();
+        while let Some(digit) = self.peek().and_then(|c| if c.is_ascii_digit() { Some(c) } else { unsubscript_digit(c) }) {
+            digits.push(digit);
+            self.position += 1;
+        }
+        if digits.is_empty() {
+            return Ok(None);
+        }
+        match digits.parse::<u32>() {
+            Ok(count) if count > 0 => Ok(Some(count)),
+            _ => Err(ConversionError::InvalidSubscript { subscript: self.chars[start..self.position].iter().collect(), position: start })
+        }
+    }
+
+    /// Parses a charge like `^2-`, `^-2`, `²⁻` or a lone `+`. Returns 0 if there is no charge here
+    fn charge(&mut self) -> Result<i32, ConversionError> {
+        let superscript = match self.peek() {
+            Some('^') => {
+                self.position += 1;
+                false
+            },
+            Some(c) if superscript_value(c).is_some() => true,
+            Some('+' | '-') => false,
+            _ => return Ok(0),
+        };
+        let digit = |c: char| match superscript {
+            true => superscript_value(c).and_then(|value| char::from_digit(value, 10)),
+            false => c.is_ascii_digit().then_some(c),
+        };
+        let sign = |c: char| match (superscript, c) {
+            (true, '⁺') | (false, '+') => Some(1),
+            (true, '⁻') | (false, '-') => Some(-1),
+            _ => None,
+        };
+        let leading_sign = self.peek().and_then(sign);
+        if leading_sign.is_some() {
+            self.position += 1;
+        }
+        let mut digits = String::new();
+        while let Some(d) = self.peek().and_then(digit) {
+            digits.push(d);
+            self.position += 1;
+        }
+        let sign = match leading_sign {
+            Some(sign) => sign,
+            None => match self.peek().and_then(sign) {
+                Some(sign) => {
+                    self.position += 1;
+                    sign
+                },
+                None => return Err(self.error("expected '+' or '-' after the charge")),
+            },
+        };
+        let magnitude = match digits.as_str() {
+            "" => 1,
+            digits => digits.parse::<i32>().map_err(|_| self.error("the charge is too large"))?,
+        };
+        Ok(sign * magnitude)
+    }
+
+    /// Adds `count` of every element of `part` into `elements`
+    fn add(&self, elements: &mut Vec<(usize, u32)>, part: &[(usize, u32)], count: u32) -> Result<(), ConversionError> {
+        for (atomic_number, atoms) in part {
+            let atoms = atoms.checked_mul(count).ok_or_else(|| self.error("too many atoms"))?;
+            match elements.iter_mut().find(|(existing, _)| existing == atomic_number) {
+                Some((_, total)) => *total = total.checked_add(atoms).ok_or_else(|| self.error("too many atoms"))?,
+                None => elements.push((*atomic_number, atoms)),
+            }
+        }
+        Ok(())
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.position).copied()
+    }
+
+    fn peek_past_spaces(&self) -> Option<char> {
+        self.chars[self.position..].iter().copied().find(|c| !c.is_whitespace())
+    }
+
+    fn skip_spaces(&mut self) {
+        while self.peek().is_some_and(char::is_whitespace) {
+            self.position += 1;
+        }
+    }
+
+    fn error(&self, reason: impl Into<String>) -> ConversionError {
+        ConversionError::InvalidFormula {
+            formula: self.formula.to_string(),
+            position: self.position,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Returns the digit that a superscript stands for, like 2 for `²`, with 10 for the signs `⁺` and `⁻`
+fn superscript_value(c: char) -> Option<u32> {
+    match c {
+        '⁰' => Some(0),
+        '¹' => Some(1),
+        '²' => Some(2),
+        '³' => Some(3),
+        '⁴'..='⁹' => Some(c as u32 - '⁴' as u32 + 4),
+        '⁺' | '⁻' => Some(10),
+        _ => None
+    }
+}
+
+fn superscript_digit(digit: char) -> char {
+    match digit {
+        '0' => '⁰',
+        '1' => '¹',
+        '2' => '²',
+        '3' => '³',
+        '4' => '⁴',
+        '5' => '⁵',
+        '6' => '⁶',
+        '7' => '⁷',
+        '8' => '⁸',
+        '9' => '⁹',
+        _ => panic!("All chars must be ascii digits when creating superscript")
+    }
+}
@@ -0,0 +1,18 @@
+//! The conversion engine behind the `unit_conversions` REPL.<br>
+//! A [`Registry`] holds every unit, conversion factor and element, and performs conversions between them.
+mod error;
+mod parsing;
+mod registry;
+pub mod algorithm;
+pub mod formula;
+pub mod prefixes;
+pub mod rational;
+pub mod reaction;
+pub mod report;
+pub mod significant_figures;
+pub mod structs;
+
+pub use error::ConversionError;
+pub use registry::Registry;
+pub use report::Report;
+pub use structs::{ConversionResult, Inconsistency, LimitingReagent, Query, Step};
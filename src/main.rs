@@ -1,609 +1,505 @@
-mod structs;
-mod algorithm;
-use structs::{Conversion, Element, IDGenerator, Step, Unit};
-use std::{collections::{HashMap, HashSet}, fs, io, path::Path};
-use fast_float;
+use unit_conversions::{ConversionError, ConversionResult, LimitingReagent, Registry, Report};
+use unit_conversions::report::LimitingReport;
+use unit_conversions::significant_figures;
+use unit_conversions::structs::Unit;
+use std::{collections::HashMap, env, fs, io::{self, BufRead, IsTerminal}, path::Path, process};
 use unicode_segmentation::UnicodeSegmentation;
 
 const ERR_ID_UNDEFINED: &str = "UnitIDs HashMap is missing a definition for an ID";
-const ERR_FILE_READ: &str = "File read must not fail";
-const AVAGADROS_CONSTANT: f64 = 6.02214076e23;
+const USAGE: &str = "\
+Usage: unit_conversions [OPTIONS] [CONVERSION...]
+       unit_conversions check [TOLERANCE]
+       unit_conversions mm FORMULA
+       unit_conversions empirical COMPOSITION [: MOLAR MASS]
+       unit_conversions balance REACTION
+
+With no conversion, starts an interactive session, or runs piped stdin as a script.
+With a conversion, like \"1.3 meter : feet\", prints the result and exits.
+With `check`, lists the cycles of conversions whose factors do not multiply to 1 within TOLERANCE, and fails if there are any.
+With `mm`, prints the molar mass of a chemical, like \"Ca(OH)2\", and the mass percent of each element in it.
+With `empirical`, finds the empirical formula of a compound from its composition, like \"C 40.0%, H 6.7%, O 53.3%\",
+and its molecular formula if its molar mass follows, like \": 180.16 g/mol\".
+With `balance`, prints a reaction, like \"C3H8 + O2 -> CO2 + H2O\", with the smallest whole coefficients that balance it.
+The exit status is 0 if everything succeeded, 1 if a conversion or script line failed and 2 if the arguments were invalid.
+
+Options:
+    -f, --script FILE  Run every line of FILE as a command, or of stdin if FILE is '-'
+    -s, --steps        Print every step of the conversion (default)
+    -a, --answer-only  Print only the answer
+    -e, --exact        Print only the answer, as an exact fraction like 63360/1 if every factor is exact
+    -u, --unrounded    Also print the answer before it is rounded to its significant figures
+    -j, --json         Print each result or error as one line of JSON
+    -h, --help         Print this message";
+
+const HELP_FILE_PATH: &str = r#"./help.txt"#;
+const CONVERSIONS_FILE_PATH: &str = r#"./conversions.txt"#;
+const ELEMENTS_FILE_PATH: &str = r#"./elements.txt"#;
+/// How far from 1 the factors around a cycle of conversions can multiply to before `check` reports it
+const DEFAULT_TOLERANCE: f64 = 1e-9;
+/// How far from a whole number a mole ratio can be, after it is multiplied, to be rounded to it by `empirical`
+const DEFAULT_RATIO_TOLERANCE: f64 = 0.1;
+
+/// How the result of a conversion is printed
+#[derive(Clone, Copy)]
+enum OutputStyle {
+    Steps,
+    AnswerOnly,
+    Exact,
+    Json,
+}
 
-fn main() {
-    let help_file_path = Path::new(r#"./help.txt"#);
-    let conversions_file_path = Path::new(r#"./conversions.txt"#);
-    let elements_file_path = Path::new(r#"./elements.txt"#);
-    let mut units_generator: IDGenerator = IDGenerator::new(0);
-    let mut elements_generator: IDGenerator = IDGenerator::new(1);
-    let mut unit_ids = HashMap::<usize, Unit>::new();
-    let mut unit_aliases = HashMap::<String, usize>::new();
-    let mut element_ids = HashMap::<usize, Element>::new();
-    let mut element_aliases = HashMap::<String, usize>::new();
-    
-    load_units_from_file(&mut units_generator, &mut unit_aliases, &mut unit_ids, conversions_file_path);
-    load_elements_from_file(&mut elements_generator, &mut element_aliases, &mut element_ids, elements_file_path);
-
-    let mut previous_answer: Option<String> = None;
-    loop {
-        let line = read_input("\nEnter a command, or `help`:");
-        if line.eq("quit;") {
-            break;
-        }
-        if line.eq("help;") {
-            println!("{}", fs::read_to_string(help_file_path).expect(ERR_FILE_READ));
-            continue;
-        }
-        if line.eq("list;") {
-            println!("All currently registered units:");
-            for id in 0..units_generator.peek() {
-                println!("\t{}: {}", id, unit_ids.get(&id).expect(ERR_ID_UNDEFINED).get_name());
-            }
-            continue;
-        }
-        if line.eq("reload;") {
-            units_generator.clear();
-            elements_generator.clear();
-            unit_ids.clear();
-            unit_aliases.clear();
-            element_ids.clear();
-            element_aliases.clear();
-            previous_answer = None;
-            load_units_from_file(&mut units_generator, &mut unit_aliases, &mut unit_ids, conversions_file_path);
-            load_elements_from_file(&mut elements_generator, &mut unit_aliases, &mut element_ids, elements_file_path);
-            println!("Reloaded!");
-            continue;
-        }
+/// How results are printed, set by the options on the command line
+#[derive(Clone, Copy)]
+struct Output {
+    style: OutputStyle,
+    /// Print the answer with every digit that was calculated next to the rounded one
+    unrounded: bool,
+}
 
-        match line.chars().next() {
-            None => panic!("Line must not be empty"),
-            Some('#') => create_unit(&mut units_generator, &mut unit_aliases, &mut unit_ids, line, true),
-            Some('$') => create_conversion(&mut unit_aliases, &mut unit_ids, line, true),
-            _ => attempt_conversion(line, &unit_aliases, &element_aliases, &element_ids, &mut unit_ids, &mut units_generator, &mut previous_answer)
-        };
-    }
+/// Whether to keep reading commands after one has been executed
+enum Flow {
+    Continue,
+    Quit,
 }
 
-fn create_unit(
-    generator: &mut IDGenerator, 
-    aliases: &mut HashMap<String, usize>, 
-    unit_ids: &mut HashMap<usize, Unit>, 
-    line: String, 
-    from_user: bool)
-{
-    fn push_word_to_names(move_next_word_up: bool, names: &mut Vec<String>, word: &String) {
-        if move_next_word_up {
-            names.insert(names.len() - 1, word.clone());
-        } else {
-            names.push(word.clone());
-        }
-    }
-    let mut names: Vec<String> = Vec::new();
-    let mut word = String::new();
-    let mut state: u8 = 0;
-    let mut move_next_word_up = false;
-    for c in line.chars() {
-        if c.is_ascii_digit() { 
-            if from_user {
-                println!("Cannot create unit with a digit ({c}) in its name");
-            } else {
-                panic!("Cannot create unit with a digit ({c}) in its name");
-            }
-        }
-        match state {
-            0 => {
-                assert!(c == '#', "Cannot create unit from a line that does not begin with '#'");
-                state = 1;
+fn main() {
+    let mut output = Output { style: OutputStyle::Steps, unrounded: false };
+    let mut script: Option<String> = None;
+    let mut conversion = Vec::new();
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "-s" | "--steps" => output.style = OutputStyle::Steps,
+            "-a" | "--answer-only" => output.style = OutputStyle::AnswerOnly,
+            "-e" | "--exact" => output.style = OutputStyle::Exact,
+            "-j" | "--json" => output.style = OutputStyle::Json,
+            "-u" | "--unrounded" => output.unrounded = true,
+            "-f" | "--script" => match args.next() {
+                Some(path) => script = Some(path),
+                None => usage_error(format!("Option '{arg}' requires a file")),
             },
-            1 => {
-                // waiting for a unit alias to begin
-                if c.is_alphabetic() {
-                    word.push(c);
-                    state = 2;
-                }
+            "-h" | "--help" => {
+                println!("{USAGE}");
+                return;
             },
-            2.. => {
-                // waiting for a unit alias to end
-                match c {
-                    '|' => {
-                        names.push(word.clone());
-                        move_next_word_up = true;
-                    },
-                    ',' => {
-                        push_word_to_names(move_next_word_up, &mut names, &word);
-                        word.clear();
-                        state = 1;
-                    },
-                    ';' => {
-                        break;
-                    }
-                    _ => {
-                        word.push(c);
-                    }
-                };
-            }
-        };
+            _ if arg.starts_with("--") => usage_error(format!("Unknown option '{arg}'")),
+            _ => conversion.push(arg)
+        }
     }
-    if word.len() > 0 {
-        push_word_to_names(move_next_word_up, &mut names, &word);
+    if script.is_some() && !conversion.is_empty() {
+        usage_error("Cannot run a script and a conversion at the same time".to_string());
     }
-    
-    let name = match names.first() {
-        None => {
-            println!("Unit definition must contain at least one alias");
-            return;
-        },
-        Some(thing) => thing
-    };
-    let unit = Unit::new(name.clone(), generator);
 
-    for n in names.iter() {
-        aliases.insert(n.to_string(), unit.get_id());
+    let mut registry = Registry::new();
+    if let Err(err) = load_files(&mut registry) {
+        eprintln!("{err}");
+        process::exit(1);
     }
 
-    unit.insert_into(unit_ids);
-    if from_user {
-        println!("Created new unit {}", name)
-    }
-}
-
-fn create_conversion(aliases: &mut HashMap<String, usize>, unit_ids: &mut HashMap<usize, Unit>, line: String, from_user: bool) {
-    let line = line.strip_prefix('$').expect("Command for creating conversion must begin with '$'").trim();
-    let (value_1, size) = match fast_float::parse_partial(line) {
-        Err(_) => (1.0, 0),
-        Ok(thing) => thing
-    };
-    let line = &line[size..];
-    let (unit_1, size, _) = match extract_unit(line, &HashSet::from(['='])) {
-        None => {
-            println!("Conversion must contain '=' to demonstrate equality");
-            return;
+    let line = conversion.join(" ");
+    let status = match script.as_deref() {
+        Some("-") => run_script(io::stdin().lock(), &mut registry, output),
+        Some(path) => match fs::File::open(path) {
+            Ok(file) => run_script(io::BufReader::new(file), &mut registry, output),
+            Err(err) => {
+                eprintln!("Could not open script '{path}': {err}");
+                1
+            }
         },
-        Some(thing) => thing
-    };
-    let line = &line.trim()[size..];
-    let (value_2, size) = match fast_float::parse_partial(line) {
-        Err(_) => (1.0, 0),
-        Ok(thing) => thing
-    };
-    let line = &line[size..];
-    let (unit_2, _, _) = extract_unit(line, &HashSet::from([';'])).expect("Conversion must contain ';' to terminate second half");
-    let one_to_two = Conversion::new(value_2, value_1);
-
-    let unit_1 = match aliases.get(&unit_1) {
-        None => {
-            println!("The first unit ({}) in that conversion is not registered", unit_1);
-            return;
+        None if is_command(&line, "check") => match check_consistency(&registry, &line) {
+            Ok(_) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
+            }
         },
-        Some(thing) => thing
-    };
-    let unit_2 = match aliases.get(&unit_2) {
-        None => {
-            println!("The second unit ({}) in that conversion is not registered", unit_2);
-            return;
+        None if is_chemistry_command(&mut registry, &line, "mm") => match print_molar_mass(&registry, &line, output) {
+            Ok(_) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
+            }
         },
-        Some(thing) => thing
-    };
-    let mut unit_1 = unit_ids.remove(unit_1).expect(ERR_ID_UNDEFINED);
-    let mut unit_2 = unit_ids.remove(unit_2).expect(ERR_ID_UNDEFINED);
-    if from_user {
-        println!("Created conversion between {} and {}", unit_1.get_name(), unit_2.get_name());
-    }
-    unit_2.push_edge(&unit_1, one_to_two.inverse());
-    unit_1.push_edge(&unit_2, one_to_two);
-    unit_1.insert_into(unit_ids);
-    unit_2.insert_into(unit_ids);
-}
-
-fn attempt_conversion(
-    line: String, 
-    unit_aliases: &HashMap<String, usize>, 
-    element_aliases: &HashMap<String, usize>,
-    element_ids: &HashMap<usize, Element>,
-    unit_ids: &mut HashMap<usize, Unit>,
-    generator: &mut IDGenerator,
-    previous_answer: &mut Option<String>)
-{
-    let line = if let Some(stripped) = line.strip_prefix("ans") {
-        match previous_answer {
-            Some(previous_answer) => {
-                let mut line = previous_answer.clone();
-                line.push_str(stripped);
-                line
-            },
-            None => {
-                println!("Cannot use 'ans': no previous answer");
-                return;
+        None if is_chemistry_command(&mut registry, &line, "empirical") => match print_empirical_formula(&registry, &line, output) {
+            Ok(_) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
             }
+        },
+        None if is_chemistry_command(&mut registry, &line, "balance") => match print_balanced(&registry, &line, output) {
+            Ok(_) => 0,
+            Err(err) => {
+                eprintln!("{err}");
+                1
+            }
+        },
+        None if !conversion.is_empty() => convert_once(&mut registry, &line, output),
+        None if !io::stdin().is_terminal() => run_script(io::stdin().lock(), &mut registry, output),
+        None => {
+            run_interactive(&mut registry, output);
+            0
         }
-    } else {
-        line
-    };
-    let (line, chemical) = try_extract_chemical(line);
-    let elements = match chemical {
-        Some(chemical) => Some(extract_elements(chemical.as_str(), element_aliases)),
-        None => None,
     };
-    let (value,starting_numers,starting_denoms,ending_numers,ending_denoms) =
-    match extract_value_and_units(line, unit_aliases) {
-        None => return,
-        Some(thing) => thing
+    process::exit(status);
+}
+
+fn usage_error(message: String) -> ! {
+    eprintln!("{message}\n\n{USAGE}");
+    process::exit(2);
+}
+
+/// Performs a single conversion from the command line and returns the exit status
+fn convert_once(registry: &mut Registry, line: &str, output: Output) -> i32 {
+    let converted = match has_several_reactants(line) {
+        true => registry.limiting_reagent(line).map(|limiting| print_limiting_reagent(registry, &limiting, output)),
+        false => registry.convert_expression(line).map(|result| print_result(registry, &result, output)),
     };
-    match convert_multiple(unit_ids, generator, element_ids, &value, &starting_numers, &starting_denoms, &ending_numers, &ending_denoms, elements) {
-        None => print!("That conversion is impossible!\n"),
-        Some((steps, answer)) => {
-            print_steps(unit_ids, value, answer, steps, &starting_numers, &starting_denoms, &ending_numers, &ending_denoms);
-            previous_answer.replace(convert_quantity_to_string(unit_ids, answer, &ending_numers, &ending_denoms));
+    match converted {
+        Ok(()) => 0,
+        Err(err) => {
+            eprintln!("{}", report_error(&err, output.style));
+            1
         }
     }
 }
 
-/// Returns the content within braces at the beggining of the line.
-/// The first string is the line without the chemical and the second string
-/// is the chemical without braces
-fn try_extract_chemical(line: String) -> (String, Option<String>) {
-    match line.split_once('[') {
-        None => (line, None),
-        Some((prefix, suffix)) => {
-            if let Some((presuffix, sufsuffix)) = suffix.split_once(']') {
-                let mut line = prefix.to_string();
-                line.push_str(sufsuffix);
-                (line, Some(presuffix.to_string()))
-            } else {
-                println!("Opening brace without closing brace!");
-                (line, None)
-            }
-        }
+/// Prints an error as one line of JSON if that is the output style, and returns its message for the caller to show
+fn report_error(err: &ConversionError, style: OutputStyle) -> String {
+    if let OutputStyle::Json = style {
+        println!("{}", Report::from(err.clone()).to_json());
     }
+    err.to_string()
 }
 
-fn extract_value_and_units(line: String, unit_aliases: &HashMap<String, usize>) -> Option<(f64, Vec<usize>, Vec<usize>, Vec<usize>, Vec<usize>)> {
-    let mut running_value = 1f64;
-    let mut size = 0;
-    let mut starting_numers = Vec::new();
-    let mut starting_denoms = Vec::new();
-    let mut ending_numers = Vec::new();
-    let mut ending_denoms = Vec::new();
-    let mut previous_terminator = '*';
-    let mut switched_to_end = false;
-    loop {
-        let line = line[size..].trim();
-        // this if,loop,if is the best way to ensure that there are no numbers in the second half of the expression
-        if switched_to_end || previous_terminator == ':'{
-            for c in line.chars() {
-                if c.is_ascii_digit() {
-                    println!("Invalid Conversion: Improper placement of number after the separating ':'");
-                    return None;
-                }
+/// Executes every line of `script` like it was typed into the interactive session, without the prompts.<br>
+/// Blank lines are skipped, failures are reported with their line number, and the script ends at `quit` or EOF.
+/// Returns the exit status
+fn run_script(script: impl BufRead, registry: &mut Registry, output: Output) -> i32 {
+    let mut previous_answer: Option<ConversionResult> = None;
+    let mut status = 0;
+    for (index, line) in script.lines().enumerate() {
+        let line = match line {
+            Ok(line) => line,
+            Err(err) => {
+                eprintln!("line {}: {err}", index + 1);
+                return 1;
             }
-        }
-        // extract a value before the unit if it is there
-        let (next_value, value_size) = match fast_float::parse_partial(&line) {
-            Err(_) => (1f64, 0),
-            Ok(thing) => thing
-        };
-        let line = line[value_size..].trim();
-        let (unit, unit_size, next_terminator) = match extract_unit(line, &HashSet::from([';', ':', '*', '/'])) {
-            None => break,
-            Some(thing) => thing
         };
-        if next_value != 1f64 {
-            running_value *= match previous_terminator {
-                '*' => next_value,
-                '/' => next_value.recip(),
-                _ => panic!("Previous Terminator ({}) must be '*' or '/' when updating running_value", previous_terminator)
-            }
+        let mut line = line.trim().to_string();
+        if line.is_empty() {
+            continue;
         }
-        if unit.len() > 0 {
-            if !process_and_push_unit(unit, unit_aliases, previous_terminator, &mut switched_to_end, &mut starting_numers, &mut ending_numers, &mut starting_denoms, &mut ending_denoms) {
-                return None
+        line.push(';');
+        match execute(line, registry, &mut previous_answer, output) {
+            Ok(Flow::Continue) => {},
+            Ok(Flow::Quit) => break,
+            Err(err) => {
+                eprintln!("line {}: {err}", index + 1);
+                status = 1;
             }
         }
-        size += unit_size + value_size;
-        previous_terminator = next_terminator;
     }
-    Some((running_value, starting_numers, starting_denoms, ending_numers, ending_denoms))
+    status
 }
 
-fn process_and_push_unit(
-    unit: String,
-    unit_aliases: &HashMap<String, usize>,
-    previous_terminator: char,
-    switched_to_end: &mut bool,
-    starting_numers: &mut Vec<usize>,
-    ending_numers: &mut Vec<usize>,
-    starting_denoms: &mut Vec<usize>,
-    ending_denoms: &mut Vec<usize>
-) -> bool {
-    let (unit, exponent) = if let Some((prefix, suffix)) = unit.split_once('^') {
-        match i32::from_str_radix(suffix.trim(), 10) {
-            Ok(exponent) => (prefix.to_string(), exponent),
-            Err(error) => {
-                println!("Invalid Conversion: Improper use of exponent, {}", error);
-                return false;
-            }
+fn run_interactive(registry: &mut Registry, output: Output) {
+    let mut previous_answer: Option<ConversionResult> = None;
+    while let Some(line) = read_input("\nEnter a command, or `help`:") {
+        match execute(line, registry, &mut previous_answer, output) {
+            Ok(Flow::Continue) => {},
+            Ok(Flow::Quit) => break,
+            Err(err) => println!("{err}"),
         }
-    } else {
-        (unit, 1)
-    };
-    let id = match unit_aliases.get(unit.as_str()) {
-        None => {
-            println!("Invalid Conversion: Unit '{}' is not registered.", unit);
-            return false;
+    }
+}
+
+/// Executes one command, definition or conversion, the same way for the interactive session and for scripts
+fn execute(line: String, registry: &mut Registry, previous_answer: &mut Option<ConversionResult>, output: Output) -> Result<Flow, String> {
+    match line.as_str() {
+        "quit;" => return Ok(Flow::Quit),
+        "help;" => match fs::read_to_string(HELP_FILE_PATH) {
+            Ok(help) => println!("{help}"),
+            Err(err) => return Err(format!("Could not read {HELP_FILE_PATH}: {err}")),
         },
-        Some(id) => *id
-    };
-    let chosen_vec = match previous_terminator {
-        '*' => {
-            match *switched_to_end {
-                false => starting_numers, 
-                true => ending_numers,
+        "list;" => {
+            println!("All currently registered units:");
+            for unit in registry.units() {
+                match unit.get_dimension() {
+                    None => println!("\t{}: {}", unit.get_id(), unit.get_name()),
+                    Some(dimension) => println!("\t{}: {} ({})", unit.get_id(), unit.get_name(), dimension),
+                }
             }
         },
-        '/' => {
-            match *switched_to_end {
-                false => starting_denoms,
-                true  => ending_denoms,
-            }
+        "reload;" => {
+            registry.clear();
+            previous_answer.take();
+            load_files(registry).map_err(|err| err.to_string())?;
+            println!("Reloaded!");
         },
-        ':' => {
-            *switched_to_end = true;
-            ending_numers
+        _ if is_command(&line, "check") => return check_consistency(registry, &line),
+        _ if is_chemistry_command(registry, &line, "mm") => return print_molar_mass(registry, &line, output),
+        _ if is_chemistry_command(registry, &line, "empirical") => return print_empirical_formula(registry, &line, output),
+        _ if is_chemistry_command(registry, &line, "balance") => return print_balanced(registry, &line, output),
+        _ => match line.chars().next() {
+            Some('#') => {
+                let id = registry.define_unit(&line).map_err(|err| err.to_string())?;
+                println!("Created new unit {}", registry.unit(id).expect(ERR_ID_UNDEFINED).get_name());
+            },
+            Some('$') => match registry.define_conversion(&line).map_err(|err| err.to_string())? {
+                (unit_1, unit_2) if unit_1 == unit_2 => println!("Defined {} as a product of other units",
+                    registry.unit(unit_1).expect(ERR_ID_UNDEFINED).get_name()),
+                (unit_1, unit_2) => println!("Created conversion between {} and {}",
+                    registry.unit(unit_1).expect(ERR_ID_UNDEFINED).get_name(),
+                    registry.unit(unit_2).expect(ERR_ID_UNDEFINED).get_name()),
+            },
+            _ => attempt_conversion(line, registry, previous_answer, output)?
         }
-        _ => panic!("Previous terminator ({}) must be '*', '/', or ':'", previous_terminator)
-    };
-    for _ in 0..exponent {
-        chosen_vec.push(id);
     }
-    return true
+    Ok(Flow::Continue)
 }
 
-fn extract_elements(chemical: &str, aliases: &HashMap<String, usize>) -> Vec<(usize, u16)>{
-    fn finish_current(current_elem: &mut String, current_num: &mut String, elements: &mut Vec<(usize, u16)>, aliases: &HashMap<String, usize>) {
-        if current_elem.len() > 0 {
-            let subscript = if current_num.is_empty() {
-                1
-            } else {
-                match u16::from_str_radix(current_num.as_str(), 10) {
-                    Ok(subscript) => subscript,
-                    Err(err) => panic!("Error parsing num when extracting elements: {err}")
-                }
-            };
-            elements.push((*aliases.get(current_elem.as_str()).expect("Invalid element"), subscript));
-            current_elem.clear();
-            current_num.clear();
-        }
-    }
-    let mut elements = Vec::new();
-    let mut current_elem = String::new();
-    let mut current_num = String::new();
-    for ch in chemical.chars() {
-        if ch.is_ascii_uppercase() {
-            finish_current(&mut current_elem, &mut current_num, &mut elements, aliases);
-            current_elem.push(ch);
-        } else if ch.is_ascii_lowercase() {
-            current_elem.push(ch);
-        } else if ch.is_ascii_digit() {
-            current_num.push(ch);
-        }
-    }
-    finish_current(&mut current_elem, &mut current_num, &mut elements, aliases);
-    elements
+/// Whether `line` starts with the `keyword` of a command, followed by whitespace or the end of the line
+fn is_command(line: &str, keyword: &str) -> bool {
+    line.strip_prefix(keyword).is_some_and(|rest| rest.chars().next().is_none_or(|next| next.is_whitespace() || next == ';'))
 }
 
-fn convert_quantity_to_string(unit_ids: &HashMap<usize, Unit>, value: f64, numers: &Vec<usize>, denoms: &Vec<usize>) -> String {
-    let mut numer_iter = numers.iter();
-    let numer = numer_iter.next().expect("Quantity must have at least one numerator unit");
-    let numer = unit_ids.get(numer).expect(ERR_ID_UNDEFINED);
-
-    let mut s = value.to_string();
-    s.push(' ');
-    s.push_str(numer.get_name());
-    for numer in numer_iter {
-        let numer = unit_ids.get(numer).expect(ERR_ID_UNDEFINED);
-        s.push_str(format!(" * {}", numer.get_name()).as_str());
-    }
-    for denom in denoms {
-        let denom = unit_ids.get(denom).expect(ERR_ID_UNDEFINED);
-        s.push_str(format!(" / {}", denom.get_name()).as_str());
-    }
-    s
+/// Whether `line` is the chemistry command `keyword`. A line with a `:` that starts with a unit of the same name, like `mm / s : m / s`, is a conversion instead
+fn is_chemistry_command(registry: &mut Registry, line: &str, keyword: &str) -> bool {
+    is_command(line, keyword) && !(line.contains(':') && registry.resolve_unit(keyword).is_some())
 }
 
-fn convert_multiple(
-    unit_ids: &mut HashMap<usize, Unit>,
-    generator: &mut IDGenerator,
-    element_ids: &HashMap<usize, Element>,
-    value: &f64,
-    starting_numers: &Vec<usize>,
-    starting_denoms: &Vec<usize>,
-    ending_numers: &Vec<usize>,
-    ending_denoms: &Vec<usize>,
-    elements: Option<Vec<(usize, u16)>>
-)-> Option<(Vec<Step>, f64)> {
-    if starting_numers.len() != ending_numers.len() {
-        print!("Starting and ending numerators must be equal in length! ");
-        return None;
-    }
-    if starting_denoms.len() != ending_denoms.len() {
-        print!("Starting and ending denominators must be equal in length! ");
-        return None;
-    }
-    insert_elements(generator, unit_ids, element_ids, elements);
-    let graph = generate_graph(generator, unit_ids);
-    let mut steps = Vec::<Step>::new();
-    let mut running_answer = *value;
-    for path in algorithm::find_paths_between(starting_numers, ending_numers, &graph) {
-        add_steps(path, unit_ids, &mut running_answer, &mut steps, false);
-    }
-    for path in algorithm::find_paths_between(starting_denoms, ending_denoms, &graph) {
-        add_steps(path, unit_ids, &mut running_answer, &mut steps, true);
-    }
-    match steps.len() {
-        0 => None,
-        1.. => Some((steps, running_answer))
+/// Lists every cycle of conversions that is inconsistent, given a line like `check` or `check 1e-6`
+fn check_consistency(registry: &Registry, line: &str) -> Result<Flow, String> {
+    let tolerance = match line.trim_start_matches("check").trim_end_matches(';').trim() {
+        "" => DEFAULT_TOLERANCE,
+        tolerance => tolerance.parse::<f64>().map_err(|_| format!("Invalid tolerance '{tolerance}'"))?,
+    };
+    let inconsistencies = registry.check_consistency(tolerance);
+    if inconsistencies.is_empty() {
+        println!("Every cycle of conversions is consistent within {tolerance}");
+        return Ok(Flow::Continue);
+    }
+    let name = |id: &usize| registry.unit(*id).expect(ERR_ID_UNDEFINED).get_name();
+    for inconsistency in &inconsistencies {
+        let cycle = inconsistency.cycle.iter().map(name).collect::<Vec<_>>().join(" → ");
+        println!("{cycle} multiplies to {}", inconsistency.factor);
+        for definition in &inconsistency.definitions {
+            println!("\t{definition}");
+        }
     }
+    Err(format!("Found {} inconsistent cycles of conversions", inconsistencies.len()))
 }
 
-fn insert_elements(generator: &mut IDGenerator, unit_ids: &mut HashMap<usize, Unit>, element_ids: &HashMap<usize, Element>, elements: Option<Vec<(usize, u16)>>) {
-    let elements = match elements {
-        Some(elements) => elements,
-        None => return
-    };
-    let (molar_mass, name) = find_mm_and_name(&elements, element_ids).unwrap();
-    let mut moles_name = String::from("moles [");
-    moles_name.push_str(name.as_str());
-    moles_name.push(']');
-    let mut grams_name = String::from("grams [");
-    grams_name.push_str(name.as_str());
-    grams_name.push(']');
-    let mut parts_name = String::from("particles [");
-    parts_name.push_str(name.as_str());
-    parts_name.push(']');
-    let mut moles = Unit::new(moles_name, generator);
-    let mut grams = Unit::new(grams_name, generator);
-    let mut particles = Unit::new(parts_name, generator);
-    let moles_to_grams = Conversion::new(molar_mass, 1f64);
-    let moles_to_particles = Conversion::new(AVAGADROS_CONSTANT, 1f64);
-    grams.push_edge(&moles, moles_to_grams.inverse());
-    particles.push_edge(&moles, moles_to_particles.inverse());
-    moles.push_edge(&grams, moles_to_grams);
-    moles.push_edge(&particles, moles_to_particles);
-    moles.insert_into(unit_ids);
-    grams.insert_into(unit_ids);
-    particles.insert_into(unit_ids);
+/// Prints the molar mass of a chemical and each element's share of it, given a line like `mm Ca(OH)2`
+fn print_molar_mass(registry: &Registry, line: &str, output: Output) -> Result<Flow, String> {
+    let formula = line.trim_start_matches("mm").trim_end_matches(';').trim();
+    let report = registry.molar_mass(formula).map_err(|err| report_error(&err, output.style))?;
+    let total = format!("{} g/mol", significant_figures::format_with_uncertainty(report.molar_mass, report.uncertainty));
+    match output.style {
+        OutputStyle::Json => println!("{}", report.to_json()),
+        OutputStyle::AnswerOnly | OutputStyle::Exact => println!("{total}"),
+        OutputStyle::Steps => {
+            let mut rows = vec![["Element", "Count", "Atomic weight", "Mass (g/mol)", "Mass %"].map(String::from)];
+            for share in &report.elements {
+                let weight = match share.standard_weight {
+                    true => share.atomic_weight.to_string(),
+                    false => format!("[{}]", share.atomic_weight),
+                };
+                rows.push([
+                    format!("{} ({})", share.symbol, share.name),
+                    share.count.to_string(),
+                    weight,
+                    share.mass.to_string(),
+                    format!("{:.2}%", share.mass_percent),
+                ]);
+            }
+            let widths: Vec<usize> = (0..5).map(|column| rows.iter().map(|row| row[column].graphemes(true).count()).max().unwrap_or(0)).collect();
+            println!();
+            for row in rows {
+                let cells: Vec<String> = row.iter().zip(&widths)
+                    .map(|(cell, width)| format!("{cell}{}", " ".repeat(width - cell.graphemes(true).count())))
+                    .collect();
+                println!("{}", cells.join("  ").trim_end());
+            }
+            println!("\nMolar mass of {}: {total}\n", report.formula);
+        },
+    }
+    Ok(Flow::Continue)
 }
 
-fn find_mm_and_name(elements: &Vec<(usize, u16)>, element_ids: &HashMap<usize, Element>) -> Result<(f64, String), String>{
-    let mut molar_mass = 0f64;
-    let mut name = String::new();
-    for (atomic_number, count) in elements {
-        match element_ids.get(atomic_number) {
-            None => return Err(format!("Atomic number {} is undefined in the given `element_ids`", atomic_number)),
-            Some(element) => {
-                molar_mass += element.molar_mass * f64::from(*count);
-                name.push_str(element.symbol.as_str());
-                name.push_str(subscript_number(*count).as_str())
+/// Prints how the empirical formula of a compound follows from its composition, given a line like `empirical C 40.0%, H 6.7%, O 53.3%`,
+/// and its molecular formula if the line ends with its molar mass, like `: 180.16 g/mol`
+fn print_empirical_formula(registry: &Registry, line: &str, output: Output) -> Result<Flow, String> {
+    let composition = line.trim_start_matches("empirical");
+    let report = registry.empirical_formula(composition, DEFAULT_RATIO_TOLERANCE).map_err(|err| report_error(&err, output.style))?;
+    let molecular = report.molecular_multiple.zip(report.molecular_formula.as_ref()).zip(report.molar_mass);
+    match output.style {
+        OutputStyle::Json => println!("{}", report.to_json()),
+        OutputStyle::AnswerOnly | OutputStyle::Exact => println!("{}", report.molecular_formula.as_ref().unwrap_or(&report.empirical_formula)),
+        OutputStyle::Steps => {
+            // every element's moles are divided by the fewest moles of any of them, shown with the figures of its amount
+            let written = |value: f64, figures: Option<u32>| match figures {
+                Some(figures) => significant_figures::format(value, figures),
+                None => value.to_string(),
+            };
+            let fewest = report.elements.iter().min_by(|a, b| a.moles.total_cmp(&b.moles)).map(|amount| written(amount.moles, amount.significant_figures));
+            for amount in &report.elements {
+                let (mut top, mut middle, mut bottom) = (String::new(), String::new(), String::new());
+                let start = format!("{} g {}", written(amount.grams, amount.significant_figures), amount.symbol);
+                let whitespace = " ".repeat(start.graphemes(true).count());
+                top.push_str(&whitespace);
+                middle.push_str(&start);
+                bottom.push_str(&whitespace);
+                push_fraction(&mut top, &mut middle, &mut bottom, format!("1 mol {}", amount.symbol), format!("{} g {}", amount.atomic_weight, amount.symbol));
+                push_term(&mut top, &mut middle, &mut bottom, format!("= {} mol {}", written(amount.moles, amount.significant_figures), amount.symbol));
+                push_term(&mut top, &mut middle, &mut bottom, format!("  ÷ {} mol = {:.3}", fewest.as_deref().unwrap_or_default(), amount.ratio));
+                if report.multiplier > 1 {
+                    push_term(&mut top, &mut middle, &mut bottom, format!("× {} = {:.3}", report.multiplier, amount.ratio * f64::from(report.multiplier)));
+                }
+                push_term(&mut top, &mut middle, &mut bottom, format!("≈ {}", amount.count));
+                println!("\n{top}\n{middle}\n{bottom}");
             }
-        };
+            println!("\nEmpirical formula: {} ({} g/mol)", report.empirical_formula, report.empirical_molar_mass);
+            if let Some(((multiple, formula), molar_mass)) = molecular {
+                println!("Molecular formula: {formula} ({molar_mass} g/mol ÷ {} g/mol ≈ {multiple})", report.empirical_molar_mass);
+            }
+            println!();
+        },
     }
-    Ok((molar_mass, name))
+    Ok(Flow::Continue)
 }
 
-fn subscript_number(num: u16) -> String {
-    let mut subscript = String::new();
-    for char in num.to_string().chars() {
-        subscript.push(match char {
-            '0' => '₀',
-            '1' => '₁',
-            '2' => '₂',
-            '3' => '₃',
-            '4' => '₄',
-            '5' => '₅',
-            '6' => '₆',
-            '7' => '₇',
-            '8' => '₈',
-            '9' => '₉',
-            _ => panic!("All chars must be ascii digits when creating subscript")
-        })
+/// Prints a reaction with the coefficients that balance it, given a line like `balance C3H8 + O2 -> CO2 + H2O`
+fn print_balanced(registry: &Registry, line: &str, output: Output) -> Result<Flow, String> {
+    let reaction = line.trim_start_matches("balance").trim_end_matches(';');
+    let reaction = registry.balance(reaction).map_err(|err| report_error(&err, output.style))?;
+    match output.style {
+        OutputStyle::Json => println!("{}", reaction.to_json()),
+        _ => println!("{reaction}"),
     }
-    subscript
+    Ok(Flow::Continue)
 }
 
-fn generate_graph(generator: &IDGenerator, unit_ids: &HashMap<usize, Unit>) -> Vec<Vec<usize>> {
-    let mut graph = Vec::new();
-    for id in 0..generator.peek() {
-        let mut new_node = Vec::new();
-        for neighbor in unit_ids.get(&id).expect(ERR_ID_UNDEFINED).connected_ids() {
-            new_node.push(*neighbor);
-        }
-        graph.push(new_node);
-    }
-    graph
+fn load_files(registry: &mut Registry) -> Result<(), ConversionError> {
+    registry.load_units_from_file(Path::new(CONVERSIONS_FILE_PATH))?;
+    registry.load_elements_from_file(Path::new(ELEMENTS_FILE_PATH))
+}
+
+fn attempt_conversion(line: String, registry: &mut Registry, previous_answer: &mut Option<ConversionResult>, output: Output) -> Result<(), String> {
+    let converted = match line.strip_prefix("ans") {
+        // the previous answer carries on with all of its digits, and is only rounded for display
+        Some(rest) => match previous_answer.as_ref() {
+            Some(previous_answer) => registry.convert_answer(previous_answer, rest),
+            None => return Err("Cannot use 'ans': no previous answer".to_string())
+        },
+        None if has_several_reactants(&line) => {
+            let mut limiting = registry.limiting_reagent(&line).map_err(|err| report_error(&err, output.style))?;
+            print_limiting_reagent(registry, &limiting, output);
+            previous_answer.replace(limiting.results.swap_remove(limiting.limiting));
+            return Ok(());
+        },
+        None => registry.convert_expression(&line),
+    };
+    let result = converted.map_err(|err| report_error(&err, output.style))?;
+    print_result(registry, &result, output);
+    previous_answer.replace(result);
+    Ok(())
 }
 
-fn add_steps(path: Vec<usize>, unit_ids: &HashMap<usize, Unit>, running_answer: &mut f64, steps: &mut Vec<Step>, inverse: bool) {
-    for (index, this_id) in path.iter().enumerate() {
-        let next_id = match path.get(index + 1) {
-            // eventually we will be on the last id in the path and there is no next one so we break early
-            None => break,
-            Some(next) => *next
+/// Whether a conversion starts from the amounts of several reactants, like `12 g [C3H8], 50 g [O2] : g [CO2] using ...`
+fn has_several_reactants(line: &str) -> bool {
+    line.contains(" using ") && line.split_once(':').is_some_and(|(amounts, _)| amounts.contains(','))
+}
+
+/// Prints the conversion of every reactant, and then which of them is the limiting reagent
+fn print_limiting_reagent(registry: &Registry, limiting: &LimitingReagent, output: Output) {
+    if let OutputStyle::Json = output.style {
+        let report = LimitingReport {
+            results: limiting.results.iter().map(|result| registry.report(result)).collect(),
+            limiting: limiting.limiting,
         };
-        let conversion = unit_ids.get(this_id)
-            .expect("The UnitIDs HashMap must have an entry for all ids in the path")
-            .convert(next_id)
-            .expect("The path must go along units that can convert along the path");
-        
-        if inverse {
-            let inverse = &conversion.inverse();
-            inverse.apply(running_answer);
-            steps.push(Step::of(inverse,next_id, *this_id));
-        } else {
-            conversion.apply(running_answer);
-            steps.push(Step::of(conversion, *this_id, next_id));
-        }
+        println!("{}", report.to_json());
+        return;
     }
+    for result in &limiting.results {
+        print_result(registry, result, output);
+    }
+    let result = &limiting.results[limiting.limiting];
+    let query = &result.query;
+    let amount = match query.significant_figures {
+        Some(figures) => significant_figures::format(query.value, figures),
+        None => query.value.to_string(),
+    };
+    println!("Limiting reagent: {amount} {}, which makes {}",
+        registry.units_to_string(&query.starting_numers, &query.starting_denoms), registry.answer_to_string(result));
 }
 
-fn print_steps(unit_ids: &HashMap<usize, Unit>, 
-    initial_value: f64, 
-    answer: f64, 
-    steps: Vec<Step>, 
-    starting_numers: &Vec<usize>,
-    starting_denoms: &Vec<usize>,
-    ending_numers: &Vec<usize>,
-    ending_denoms: &Vec<usize>)
-{
-    fn convert_ids_to_string(ids: &Vec<usize>, unit_ids: &HashMap<usize, Unit>) -> String {
-        let mut iter = ids.iter();
-        let mut s = String::from(
-            unit_ids.get(iter.next()
-            .expect("Must have at least one unit in the numerator"))
-            .expect(ERR_ID_UNDEFINED).get_name());
-    
-        for id in iter {
-            s.push_str(" × ");
-            s.push_str(unit_ids.get(id).expect(ERR_ID_UNDEFINED).get_name());
-        }
-        s
-    }
-    
-    fn push_fraction (top: &mut String, middle: &mut String, bottom: &mut String, numer: String, denom: String) {
-        top   .push_str("⎧ ");
-        middle.push_str("⎪⎻");
-        bottom.push_str("⎩ ");
-            
-        let size = numer.len().max(denom.len());
-        top.push_str(format!("{: ^size$}", numer).as_str());
-        middle.push_str("⎻".repeat(size).as_str());
-        bottom.push_str(format!("{: ^size$}", denom).as_str());
-    
-        top   .push_str(" ⎫");
-        middle.push_str("⎻⎪");
-        bottom.push_str(" ⎭");
+fn print_result(registry: &Registry, result: &ConversionResult, output: Output) {
+    let answer = match output.unrounded {
+        true => format!("{} (unrounded {})", result.rounded_answer(), result.unrounded_answer()),
+        false => result.rounded_answer(),
+    };
+    match output.style {
+        OutputStyle::Steps => print_steps(registry.unit_ids(), result, answer),
+        OutputStyle::AnswerOnly if output.unrounded => println!("{} ({})", registry.answer_to_string(result), registry.unrounded_answer_to_string(result)),
+        OutputStyle::AnswerOnly => println!("{}", registry.answer_to_string(result)),
+        OutputStyle::Exact => match registry.exact_answer_to_string(result) {
+            Some(answer) => println!("{answer}"),
+            None => println!("{}", registry.answer_to_string(result)),
+        },
+        OutputStyle::Json => println!("{}", Report::Success(registry.report(result)).to_json()),
     }
-    
+}
+
+/// Prints the value, every step and `answer` as a chain of fractions
+fn print_steps(unit_ids: &HashMap<usize, Unit>, result: &ConversionResult, answer: String) {
+    fn convert_ids_to_string(ids: &[usize], unit_ids: &HashMap<usize, Unit>) -> String {
+        ids.iter()
+            .map(|id| unit_ids.get(id).expect(ERR_ID_UNDEFINED).get_name())
+            .collect::<Vec<_>>()
+            .join(" × ")
+    }
+
+    let query = &result.query;
+    let interval = if query.interval { "Δ" } else { "" };
+    // the value is shown with as many significant figures as it was written with, like `1.30`
+    let written_value = match (query.uncertainty, query.significant_figures) {
+        (Some(uncertainty), _) => significant_figures::format_with_uncertainty(query.value, uncertainty),
+        (None, Some(figures)) => significant_figures::format(query.value, figures),
+        (None, None) => query.value.to_string(),
+    };
     let mut bottom = String::new();
     let mut middle = String::new();
     let mut top = String::new();
 
+    // a value like `2 / s` has no units in its numerator
     let numer = format!(
-        //"{0:.3e} {1}", 
-        "{} {}",
-        initial_value, convert_ids_to_string(starting_numers, unit_ids));
-    if starting_denoms.len() == 0 {
+        //"{0:.3e} {1}",
+        "{} {}{}",
+        written_value, interval, convert_ids_to_string(&query.starting_numers, unit_ids)).trim_end().to_string();
+    if query.starting_denoms.is_empty() {
         let whitespace = " ".repeat(numer.graphemes(true).count());
         top.push_str(whitespace.as_str());
         middle.push_str(numer.as_str());
         bottom.push_str(whitespace.as_str());
     } else {
-        let denom = convert_ids_to_string(starting_denoms, unit_ids);
+        let denom = convert_ids_to_string(&query.starting_denoms, unit_ids);
         push_fraction(&mut top, &mut middle, &mut bottom, numer, denom);
     }
 
-    for step in steps {
-        let numer = step.get_top(unit_ids);
-        let denom = step.get_bottom(unit_ids);
+    for step in &result.steps {
+        // the uncertainty of a factor only matters once there is an uncertainty to carry through the steps,
+        // and it belongs to the measured side of the fraction rather than the side that is 1
+        let (numer, denom) = match query.uncertainty.is_some() && step.relative_error() > 0f64 {
+            true if step.top_value() == 1f64 => (step.get_top(unit_ids), step.get_uncertain_bottom(unit_ids)),
+            true => (step.get_uncertain_top(unit_ids), step.get_bottom(unit_ids)),
+            false => (step.get_top(unit_ids), step.get_bottom(unit_ids)),
+        };
         push_fraction(&mut top, &mut middle, &mut bottom, numer, denom);
+        if let Some(offset) = step.get_offset(unit_ids) {
+            push_term(&mut top, &mut middle, &mut bottom, offset);
+        }
     }
 
     top.push_str("   ");
@@ -611,16 +507,16 @@ fn print_steps(unit_ids: &HashMap<usize, Unit>,
     bottom.push_str("   ");
 
     let numer = format!(
-        //"{0:.3e} {1}", 
-        "{} {}",
-        answer, convert_ids_to_string(ending_numers, unit_ids));
-    if ending_denoms.len() == 0 {
+        //"{0:.3e} {1}",
+        "{} {}{}",
+        answer, interval, convert_ids_to_string(&query.ending_numers, unit_ids));
+    if query.ending_denoms.is_empty() {
         // let whitespace = " ".repeat(numer.len());
         // top.push_str(whitespace.as_str());
         middle.push_str(numer.as_str());
         // bottom.push_str(whitespace.as_str());
     } else {
-        let denom = convert_ids_to_string(ending_denoms, unit_ids);
+        let denom = convert_ids_to_string(&query.ending_denoms, unit_ids);
         push_fraction(&mut top, &mut middle, &mut bottom, numer, denom);
     }
 
@@ -629,83 +525,40 @@ fn print_steps(unit_ids: &HashMap<usize, Unit>,
     println!("{bottom}\n");
 }
 
-fn load_elements_from_file(generator: &mut IDGenerator, aliases: &mut HashMap<String, usize>, element_ids: &mut HashMap<usize, Element>, file_path: &Path) {
-    let contents = fs::read_to_string(file_path).expect(ERR_FILE_READ);
-    for line in contents.lines() {
-        let mut line = line.trim().to_string();
-        line.push(';');
-        if line.len() > 0 {
-            create_element(generator, aliases, element_ids, line);
-        }
-    }
-}
+/// Appends a fraction to the three lines of a diagram, with `numer` over `denom`
+fn push_fraction(top: &mut String, middle: &mut String, bottom: &mut String, numer: String, denom: String) {
+    top   .push_str("⎧ ");
+    middle.push_str("⎪⎻");
+    bottom.push_str("⎩ ");
 
-fn create_element(generator: &mut IDGenerator, aliases: &mut HashMap<String, usize>, element_ids: &mut HashMap<usize, Element>, line: String) {
-    let mut line = line.as_str();
-    let mut new_aliases = Vec::new();
-    loop {
-        if let Some((alias, length, terminator)) = extract_unit(line, &HashSet::from([',', '='])) {
-            line = &line[length..].trim();
-            new_aliases.push(alias);
-            if terminator == '=' {
-                break
-            }
-        } else {
-            panic!("Element file line must have an equals sign")
-        }
-    }
-    let molar_mass: f64 = match fast_float::parse_partial(line) {
-        Ok((molar_mass, _)) => molar_mass,
-        Err(err) => panic!("new element line must have a valid number after the equals sign: fast_float says {err}")
-    };
-    let atomic_number = generator.next();
-    let element = Element::new(new_aliases[0].clone(), atomic_number, molar_mass);
-    element_ids.insert(atomic_number, element);
-    for alias in new_aliases {
-        aliases.insert(alias, atomic_number);
-    }
-}
+    let size = numer.len().max(denom.len());
+    top.push_str(format!("{: ^size$}", numer).as_str());
+    middle.push_str("⎻".repeat(size).as_str());
+    bottom.push_str(format!("{: ^size$}", denom).as_str());
 
-fn load_units_from_file(
-    generator: &mut IDGenerator, 
-    aliases: &mut HashMap<String, usize>, 
-    unit_ids: &mut HashMap<usize, Unit>, 
-    file_path: &Path) 
-{
-    let contents = fs::read_to_string(file_path).expect(ERR_FILE_READ);
-    for line in contents.lines() {
-        let mut line = line.to_string();
-        line.push(';');
-        match line.chars().next() {
-            Some('#') => create_unit(generator, aliases, unit_ids, line, false),
-            Some('$') => create_conversion(aliases, unit_ids, line, false),
-            _ => continue
-        };
-    }
+    top   .push_str(" ⎫");
+    middle.push_str("⎻⎪");
+    bottom.push_str(" ⎭");
 }
 
-fn extract_unit(line: &str, termination_chars: &HashSet<char>) -> Option<(String, usize, char)> {
-    let mut unit: String = String::new();
-    let mut size: usize = 0;
-    for c in line.chars() {
-        size += 1;
-        if termination_chars.contains(&c) {
-            return Some((unit.trim().to_string(), size, c));
-        } else {
-            unit.push(c);
-        }
-    }
-    None
+/// Appends a term that sits on the middle line of a diagram, like an offset or an answer
+fn push_term(top: &mut String, middle: &mut String, bottom: &mut String, term: String) {
+    let whitespace = " ".repeat(term.graphemes(true).count() + 1);
+    top.push_str(whitespace.as_str());
+    middle.push_str(format!(" {term}").as_str());
+    bottom.push_str(whitespace.as_str());
 }
 
-fn read_input(prompt: &str) -> String {
+/// Returns the next line typed by the user with the `;` terminator, or `None` once stdin is closed
+fn read_input(prompt: &str) -> Option<String> {
     println!("{}", prompt);
     let mut input = String::new();
     match io::stdin().read_line(&mut input) {
-        Ok(_) => {},
-        Err(_) => {}
+        Ok(0) | Err(_) => None,
+        Ok(_) => {
+            input = input.trim().to_string();
+            input.push(';');
+            Some(input)
+        }
     }
-    input = input.trim().to_string();
-    input.push(';');
-    input
 }
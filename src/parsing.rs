@@ -0,0 +1,334 @@
+use crate::error::ConversionError;
+use crate::rational::{self, Rational};
+use crate::significant_figures;
+use num_traits::{One, Zero};
+use crate::structs::{Dimension, Query};
+use std::collections::HashSet;
+
+/// Splits the reaction off a conversion, like `C3H8 + 5 O2 -> 3 CO2 + 4 H2O` in `12 g [C3H8] : g [CO2] using C3H8 + 5 O2 -> 3 CO2 + 4 H2O`
+pub(crate) fn split_reaction(line: &str) -> (&str, Option<&str>) {
+    match line.split_once(" using ") {
+        None => (line, None),
+        Some((line, reaction)) => (line, Some(reaction.trim().trim_end_matches(';'))),
+    }
+}
+
+/// Returns every chemical in brackets in the line, like `C3H8` and `CO2` in `12 g [C3H8] : g [CO2]`,
+/// and the line with each of them replaced by its index, like `12 g [0] : g [1]`.<br>
+/// The formulas can contain characters that mean something else in an expression, like the `*` of `CuSO4*5H2O` or the `^` of `SO4^2-`
+pub(crate) fn extract_chemicals(line: &str) -> Result<(String, Vec<String>), ConversionError> {
+    let mut chemicals = Vec::new();
+    let mut replaced = String::new();
+    let mut rest = line;
+    while let Some((before, after)) = rest.split_once('[') {
+        let (chemical, after) = after.split_once(']').ok_or(ConversionError::UnclosedBracket)?;
+        replaced.push_str(&format!("{before}[{}]", chemicals.len()));
+        chemicals.push(chemical.trim().to_string());
+        rest = after;
+    }
+    replaced.push_str(rest);
+    Ok((replaced, chemicals))
+}
+
+/// Puts the chemicals that [`extract_chemicals`] took out of a line back in place of their indices, like `g [1]` to `g [CO2]`
+pub(crate) fn restore_chemicals(text: &str, chemicals: &[String]) -> String {
+    let mut restored = text.to_string();
+    for (index, chemical) in chemicals.iter().enumerate() {
+        restored = restored.replace(&format!("[{index}]"), &format!("[{chemical}]"));
+    }
+    restored
+}
+
+/// Splits an alias that names a chemical by its index, like `g [0]` or `[0] g`, into the unit and the index
+pub(crate) fn split_chemical_index(alias: &str) -> Option<(String, usize)> {
+    let (before, rest) = alias.split_once('[')?;
+    let (index, after) = rest.split_once(']')?;
+    let unit = format!("{} {}", before.trim(), after.trim());
+    Some((unit.trim().to_string(), index.trim().parse().ok()?))
+}
+
+/// Parses an expression like `3 m / s : ft / min`.<br>
+/// `resolve_unit` turns each alias into the id of its unit, or `None` if it is not registered
+pub(crate) fn extract_value_and_units(line: &str, resolve_unit: &mut impl FnMut(&str) -> Option<usize>) -> Result<Query, ConversionError> {
+    let mut query = Query {
+        value: 1f64,
+        exact_value: Some(Rational::one()),
+        ..Query::default()
+    };
+    let mut rest = line;
+    let mut previous_terminator = '*';
+    let mut switched_to_end = false;
+    let mut values_read = 0;
+    loop {
+        let line = rest.trim_start();
+        // extract a value before the unit if it is there. Words like `nan` in `nanoseconds` or `inf` are not values
+        let (next_value, value_size) = fast_float::parse_partial(line).ok()
+            .filter(|(_, size)| line[..*size].contains(|c: char| c.is_ascii_digit()))
+            .unwrap_or((1f64, 0));
+        let next_exact_value = rational::parse_decimal(&line[..value_size]);
+        let next_figures = significant_figures::count(&line[..value_size]);
+        // there must be no numbers in the second half of the expression, apart from exponents
+        if (switched_to_end || previous_terminator == ':') && value_size > 0 {
+            return Err(ConversionError::MisplacedNumber);
+        }
+        let (next_uncertainty, uncertainty_size) = match value_size {
+            0 => (None, 0),
+            _ => extract_uncertainty(&line[value_size..])?,
+        };
+        if value_size > 0 {
+            // a place to add offsets to only makes sense for a single number
+            query.resolution = match values_read {
+                0 => significant_figures::resolution(&line[..value_size]),
+                _ => None,
+            };
+            values_read += 1;
+            query.significant_figures = significant_figures::min(query.significant_figures, next_figures);
+            query.uncertainty = combine_uncertainties((query.value, query.uncertainty), (next_value, next_uncertainty), previous_terminator == '/');
+        }
+        let line = line[value_size + uncertainty_size..].trim_start();
+        let (unit, unit_size, next_terminator) = match extract_unit(line, &HashSet::from([';', ':', '*', '/'])) {
+            None => break,
+            Some(thing) => thing
+        };
+        if next_value != 1f64 {
+            let exact_value = query.exact_value.take().zip(next_exact_value);
+            match previous_terminator {
+                '*' => {
+                    query.value *= next_value;
+                    query.exact_value = exact_value.map(|(value, next)| value * next);
+                },
+                '/' => {
+                    query.value /= next_value;
+                    query.exact_value = exact_value.filter(|(_, next)| !next.is_zero()).map(|(value, next)| value / next);
+                },
+                _ => return Err(ConversionError::UnexpectedCharacter { character: previous_terminator })
+            }
+        }
+        // the ending units can start with a `/`, like `: / min`
+        if previous_terminator == ':' {
+            switched_to_end = true;
+        }
+        if !unit.is_empty() {
+            process_and_push_unit(unit, resolve_unit, previous_terminator, switched_to_end, &mut query)?;
+        }
+        rest = &line[unit_size..];
+        previous_terminator = next_terminator;
+    }
+    Ok(query)
+}
+
+/// Reads an uncertainty like `± 0.2` or `+- 0.2` from the start of `line`, which follows the number that it belongs to.<br>
+/// Returns the uncertainty and how many bytes it took up, or `None` and 0 if the line does not start with one
+pub(crate) fn extract_uncertainty(line: &str) -> Result<(Option<f64>, usize), ConversionError> {
+    let trimmed = line.trim_start();
+    let rest = match trimmed.strip_prefix('±').or_else(|| trimmed.strip_prefix("+-")) {
+        None => return Ok((None, 0)),
+        Some(rest) => rest.trim_start()
+    };
+    match fast_float::parse_partial::<f64, _>(rest) {
+        Ok((uncertainty, size)) if size > 0 => Ok((Some(uncertainty.abs()), line.len() - rest.len() + size)),
+        _ => Err(ConversionError::UnexpectedCharacter { character: '±' })
+    }
+}
+
+/// Returns the uncertainty of the product, or the quotient if `divide` is true, of two values with independent uncertainties.<br>
+/// Returns `None` if neither value has an uncertainty
+fn combine_uncertainties(first: (f64, Option<f64>), second: (f64, Option<f64>), divide: bool) -> Option<f64> {
+    if first.1.is_none() && second.1.is_none() {
+        return None;
+    }
+    let ((value, uncertainty), (next, next_uncertainty)) = ((first.0, first.1.unwrap_or(0f64)), (second.0, second.1.unwrap_or(0f64)));
+    Some(match divide {
+        false => (uncertainty * next).hypot(value * next_uncertainty),
+        true => (uncertainty / next).hypot(value * next_uncertainty / (next * next)),
+    })
+}
+
+fn process_and_push_unit(
+    unit: String,
+    resolve_unit: &mut impl FnMut(&str) -> Option<usize>,
+    previous_terminator: char,
+    switched_to_end: bool,
+    query: &mut Query
+) -> Result<(), ConversionError> {
+    let (unit, exponent) = if let Some((prefix, suffix)) = unit.split_once('^') {
+        match suffix.trim().parse::<i32>() {
+            Ok(exponent) => (prefix.trim().to_string(), exponent),
+            Err(_) => return Err(ConversionError::InvalidExponent { exponent: suffix.trim().to_string() })
+        }
+    } else {
+        (unit, 1)
+    };
+    let id = match resolve_unit(unit.as_str()) {
+        Some(id) => id,
+        None => match strip_interval_prefix(&unit).and_then(&mut *resolve_unit) {
+            Some(id) => {
+                query.interval = true;
+                id
+            },
+            None => return Err(ConversionError::UnknownUnit { alias: unit }),
+        }
+    };
+    if exponent == 0 {
+        return Err(ConversionError::InvalidExponent { exponent: exponent.to_string() });
+    }
+    // no dimension can have a larger exponent, so this also bounds how many ids are pushed
+    if i8::try_from(exponent).is_err() {
+        return Err(ConversionError::ExponentOutOfRange { expression: format!("{unit}^{exponent}") });
+    }
+    let (numers, denoms) = match switched_to_end {
+        false => (&mut query.starting_numers, &mut query.starting_denoms),
+        true => (&mut query.ending_numers, &mut query.ending_denoms),
+    };
+    // a negative exponent moves the unit to the other side of the fraction, so `/ s^-2` multiplies by `s^2`
+    let chosen_vec = match (previous_terminator, exponent > 0) {
+        ('*' | ':', true) | ('/', false) => numers,
+        ('/', true) | ('*' | ':', false) => denoms,
+        _ => return Err(ConversionError::UnexpectedCharacter { character: previous_terminator })
+    };
+    for _ in 0..exponent.unsigned_abs() {
+        chosen_vec.push(id);
+    }
+    Ok(())
+}
+
+/// Parses a product of base quantities, like `length^2` or `length / time`
+pub(crate) fn extract_dimension(expression: &str) -> Result<Dimension, ConversionError> {
+    let mut dimension = Dimension::default();
+    let mut divide = false;
+    let mut rest = expression.trim();
+    loop {
+        let (term, remainder, next_divide) = match rest.find(['*', '/']) {
+            None => (rest, "", false),
+            Some(index) => (&rest[..index], &rest[index + 1..], rest[index..].starts_with('/'))
+        };
+        let (quantity, exponent) = match term.split_once('^') {
+            None => (term.trim(), 1),
+            Some((quantity, exponent)) => match exponent.trim().parse::<i8>() {
+                Ok(exponent) => (quantity.trim(), exponent),
+                Err(_) => return Err(ConversionError::InvalidExponent { exponent: exponent.trim().to_string() })
+            }
+        };
+        let base = match Dimension::base(quantity) {
+            None => return Err(ConversionError::UnknownQuantity { quantity: quantity.to_string() }),
+            Some(base) => base.checked_powi(exponent)
+        };
+        let product = match divide {
+            false => base.and_then(|base| dimension.checked_mul(base)),
+            true => base.and_then(|base| dimension.checked_div(base)),
+        };
+        dimension = product.ok_or_else(|| ConversionError::ExponentOutOfRange { expression: expression.trim().to_string() })?;
+        if remainder.is_empty() {
+            break;
+        }
+        rest = remainder;
+        divide = next_divide;
+    }
+    Ok(dimension)
+}
+
+/// Removes the `delta ` or `Δ` that marks a unit as an interval, like `delta celsius` or `Δ°C`
+fn strip_interval_prefix(unit: &str) -> Option<&str> {
+    unit.strip_prefix("delta ")
+        .or_else(|| unit.strip_prefix('Δ'))
+        .map(str::trim)
+}
+
+/// An element's symbol or name, the characters before it in the composition, the grams of it,
+/// and how many significant figures they were written with
+pub(crate) type ElementMass = (String, usize, f64, Option<u32>);
+
+/// Reads a composition like `C 40.0%, H 6.7%, O 53.3%` or `Fe 2.23 g, O 0.96 g`, optionally followed by the molar mass
+/// of the compound, like `: 180.16 g/mol`.<br>
+/// Mass percents count as grams of a 100 gram sample, so they cannot be mixed with grams
+pub(crate) fn extract_composition(line: &str) -> Result<(Vec<ElementMass>, Option<f64>), ConversionError> {
+    let invalid = |reason: String| ConversionError::InvalidComposition { reason };
+    let line = line.trim().trim_end_matches(';');
+    let (amounts, molar_mass) = match line.split_once(':') {
+        None => (line, None),
+        Some((amounts, molar_mass)) => {
+            let text = molar_mass.trim().trim_end_matches("g/mol").trim();
+            match text.parse::<f64>() {
+                Ok(molar_mass) if molar_mass > 0f64 => (amounts, Some(molar_mass)),
+                _ => return Err(invalid(format!("'{}' is not a molar mass", molar_mass.trim()))),
+            }
+        }
+    };
+    let mut percents = None;
+    let mut composition = Vec::new();
+    let mut position = 0;
+    for part in amounts.split(',') {
+        let amount = part.trim();
+        let alias_position = position + part.chars().take_while(|c| c.is_whitespace()).count();
+        position += part.chars().count() + 1;
+        let number_start = amount.find(|c: char| c.is_ascii_digit() || c == '.').unwrap_or(amount.len());
+        let alias = amount[..number_start].trim();
+        let (grams, size): (f64, usize) = fast_float::parse_partial(&amount[number_start..])
+            .map_err(|_| invalid(format!("'{amount}' must be an element and an amount, like 'C 40.0%' or 'C 2.4 g'")))?;
+        let is_percent = match amount[number_start + size..].trim() {
+            "%" => true,
+            "g" | "gram" | "grams" => false,
+            unit => return Err(invalid(format!("'{unit}' is not '%' or 'g'"))),
+        };
+        if *percents.get_or_insert(is_percent) != is_percent {
+            return Err(invalid("mass percents and grams cannot be mixed".to_string()));
+        }
+        if alias.is_empty() || grams <= 0f64 {
+            return Err(invalid(format!("'{amount}' must be an element and an amount greater than 0")));
+        }
+        let figures = significant_figures::count(&amount[number_start..number_start + size]);
+        composition.push((alias.to_string(), alias_position, grams, figures));
+    }
+    Ok((composition, molar_mass))
+}
+
+pub(crate) fn subscript_number(num: u32) -> String {
+    let mut subscript = String::new();
+    for char in num.to_string().chars() {
+        subscript.push(match char {
+            '0' => '₀',
+            '1' => '₁',
+            '2' => '₂',
+            '3' => '₃',
+            '4' => '₄',
+            '5' => '₅',
+            '6' => '₆',
+            '7' => '₇',
+            '8' => '₈',
+            '9' => '₉',
+            _ => panic!("All chars must be ascii digits when creating subscript")
+        })
+    }
+    subscript
+}
+
+/// The inverse of `subscript_number` for a single char, so that names like `H₂O` can be parsed again
+pub(crate) fn unsubscript_digit(ch: char) -> Option<char> {
+    match ch {
+        '₀'..='₉' => char::from_digit(ch as u32 - '₀' as u32, 10),
+        _ => None
+    }
+}
+
+pub(crate) fn extract_unit(line: &str, termination_chars: &HashSet<char>) -> Option<(String, usize, char)> {
+    let mut unit: String = String::new();
+    let mut size: usize = 0;
+    for c in line.chars() {
+        size += c.len_utf8();
+        if termination_chars.contains(&c) {
+            return Some((unit.trim().to_string(), size, c));
+        } else {
+            unit.push(c);
+        }
+    }
+    None
+}
+
+/// Appends the `;` terminator that the parsing functions rely on, unless the line already ends with one
+pub(crate) fn terminate(line: &str) -> String {
+    let mut line = line.trim().to_string();
+    if !line.ends_with(';') {
+        line.push(';');
+    }
+    line
+}
@@ -0,0 +1,113 @@
+use crate::rational::Rational;
+use num_bigint::BigInt;
+
+/// Which prefixes a unit accepts, as marked on its `#` line
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum PrefixSet {
+    /// The SI prefixes, like kilo- and milli-
+    Metric,
+    /// The SI prefixes plus the binary prefixes, like kibi- and mebi-, for units of data
+    Binary,
+}
+
+/// A multiplier that can be put in front of a unit, like kilo- (k) for 10³
+pub struct Prefix {
+    pub name: &'static str,
+    pub symbols: &'static [&'static str],
+    base: i32,
+    exponent: i32,
+}
+
+pub const METRIC_PREFIXES: [Prefix; 24] = [
+    Prefix::new("quetta", &["Q"], 10, 30),
+    Prefix::new("ronna", &["R"], 10, 27),
+    Prefix::new("yotta", &["Y"], 10, 24),
+    Prefix::new("zetta", &["Z"], 10, 21),
+    Prefix::new("exa", &["E"], 10, 18),
+    Prefix::new("peta", &["P"], 10, 15),
+    Prefix::new("tera", &["T"], 10, 12),
+    Prefix::new("giga", &["G"], 10, 9),
+    Prefix::new("mega", &["M"], 10, 6),
+    Prefix::new("kilo", &["k"], 10, 3),
+    Prefix::new("hecto", &["h"], 10, 2),
+    Prefix::new("deca", &["da"], 10, 1),
+    Prefix::new("deci", &["d"], 10, -1),
+    Prefix::new("centi", &["c"], 10, -2),
+    Prefix::new("milli", &["m"], 10, -3),
+    Prefix::new("micro", &["µ", "μ", "u"], 10, -6),
+    Prefix::new("nano", &["n"], 10, -9),
+    Prefix::new("pico", &["p"], 10, -12),
+    Prefix::new("femto", &["f"], 10, -15),
+    Prefix::new("atto", &["a"], 10, -18),
+    Prefix::new("zepto", &["z"], 10, -21),
+    Prefix::new("yocto", &["y"], 10, -24),
+    Prefix::new("ronto", &["r"], 10, -27),
+    Prefix::new("quecto", &["q"], 10, -30),
+];
+
+pub const BINARY_PREFIXES: [Prefix; 8] = [
+    Prefix::new("kibi", &["Ki"], 1024, 1),
+    Prefix::new("mebi", &["Mi"], 1024, 2),
+    Prefix::new("gibi", &["Gi"], 1024, 3),
+    Prefix::new("tebi", &["Ti"], 1024, 4),
+    Prefix::new("pebi", &["Pi"], 1024, 5),
+    Prefix::new("exbi", &["Ei"], 1024, 6),
+    Prefix::new("zebi", &["Zi"], 1024, 7),
+    Prefix::new("yobi", &["Yi"], 1024, 8),
+];
+
+impl Prefix {
+    const fn new(name: &'static str, symbols: &'static [&'static str], base: i32, exponent: i32) -> Self {
+        Prefix { name, symbols, base, exponent }
+    }
+
+    /// Returns how many of the unprefixed unit make up one of the prefixed unit, as a fraction.<br>
+    /// Negative exponents are kept in the denominator so that `10⁻³` is exactly `1 / 1000`
+    pub fn fraction(&self) -> (f64, f64) {
+        let power = f64::from(self.base).powi(self.exponent.abs());
+        match self.exponent < 0 {
+            true => (1f64, power),
+            false => (power, 1f64)
+        }
+    }
+
+    /// Returns the same multiplier as [`fraction`](Self::fraction) as an exact fraction, which stays exact for every prefix
+    pub fn exact_fraction(&self) -> Rational {
+        let power = Rational::from_integer(BigInt::from(self.base).pow(self.exponent.unsigned_abs()));
+        match self.exponent < 0 {
+            true => power.recip(),
+            false => power
+        }
+    }
+}
+
+impl PrefixSet {
+    pub fn prefixes(self) -> impl Iterator<Item = &'static Prefix> {
+        let binary: &'static [Prefix] = match self {
+            PrefixSet::Metric => &[],
+            PrefixSet::Binary => &BINARY_PREFIXES,
+        };
+        METRIC_PREFIXES.iter().chain(binary)
+    }
+
+    pub fn contains(self, prefix: &Prefix) -> bool {
+        self.prefixes().any(|p| p.name == prefix.name)
+    }
+}
+
+/// Returns every way that `alias` can be split into a prefix and the rest of an alias,
+/// like `kilometer` into `kilo` and `meter`. Longer prefixes come first, so `dam` is tried as `da` + `m` before `d` + `am`
+pub fn split_prefix(alias: &str) -> Vec<(&'static Prefix, &str)> {
+    let mut splits = Vec::new();
+    for prefix in PrefixSet::Binary.prefixes() {
+        for written in std::iter::once(&prefix.name).chain(prefix.symbols) {
+            if let Some(rest) = alias.strip_prefix(written) {
+                if !rest.is_empty() {
+                    splits.push((prefix, rest));
+                }
+            }
+        }
+    }
+    splits.sort_by_key(|(_, rest)| rest.len());
+    splits
+}
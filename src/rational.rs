@@ -0,0 +1,143 @@
+use num_bigint::BigInt;
+use num_rational::BigRational;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+
+/// An exact fraction of arbitrarily large integers
+pub type Rational = BigRational;
+
+/// The same map as a [`Conversion`](crate::structs::Conversion), `x * scale + offset`, kept as exact fractions
+/// so that a chain of them can be composed without rounding
+#[derive(Clone, Debug, PartialEq)]
+pub struct RationalConversion {
+    pub scale: Rational,
+    pub offset: Rational,
+}
+
+impl RationalConversion {
+    /// Returns the conversion that multiplies by `scale` and adds nothing
+    pub fn scale(scale: Rational) -> Self {
+        RationalConversion { scale, offset: Rational::zero() }
+    }
+
+    /// Returns the conversion that multiplies by `numerator / denominator`, using the shortest decimal form of each float.<br>
+    /// Returns `None` if either is not finite or the denominator is zero
+    pub fn ratio(numerator: f64, denominator: f64) -> Option<Self> {
+        let denominator = from_f64(denominator).filter(|denominator| !denominator.is_zero())?;
+        Some(Self::scale(from_f64(numerator)? / denominator))
+    }
+
+    /// Creates the conversion that maps `first.0` onto `first.1` and `second.0` onto `second.1`.<br>
+    /// Returns `None` if the two points start at the same value
+    pub fn through_points(first: (&Rational, &Rational), second: (&Rational, &Rational)) -> Option<Self> {
+        let run = second.0 - first.0;
+        if run.is_zero() {
+            return None;
+        }
+        let scale = (second.1 - first.1) / run;
+        let offset = first.1 - first.0 * &scale;
+        Some(RationalConversion { scale, offset })
+    }
+
+    pub fn apply(&self, value: &Rational) -> Rational {
+        value * &self.scale + &self.offset
+    }
+
+    /// Returns the conversion that undoes this one, or `None` if it maps everything onto the same value
+    pub fn inverse(&self) -> Option<Self> {
+        if self.scale.is_zero() {
+            return None;
+        }
+        let scale = self.scale.recip();
+        let offset = -(&self.offset * &scale);
+        Some(RationalConversion { scale, offset })
+    }
+
+    /// Returns the conversion that applies this one and then `next`
+    pub fn then(&self, next: &RationalConversion) -> Self {
+        RationalConversion {
+            scale: &self.scale * &next.scale,
+            offset: &self.offset * &next.scale + &next.offset,
+        }
+    }
+
+    pub fn scale_only(&self) -> Self {
+        Self::scale(self.scale.clone())
+    }
+}
+
+/// Parses a decimal number, like `1.60934`, `-40` or `6.02214076e23`, into the exact fraction that it is written as.<br>
+/// Returns `None` for anything else, like `inf`
+pub fn parse_decimal(text: &str) -> Option<Rational> {
+    let text = text.trim();
+    let (mantissa, exponent) = match text.split_once(['e', 'E']) {
+        None => (text, 0i32),
+        Some((mantissa, exponent)) => (mantissa, exponent.strip_prefix('+').unwrap_or(exponent).parse().ok()?),
+    };
+    let (negative, mantissa) = match mantissa.strip_prefix('-') {
+        Some(mantissa) => (true, mantissa),
+        None => (false, mantissa.strip_prefix('+').unwrap_or(mantissa)),
+    };
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    let digits = format!("{whole}{fraction}");
+    if digits.is_empty() || !digits.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    let mut value = Rational::from_integer(digits.parse::<BigInt>().ok()?);
+    let exponent = exponent.checked_sub(i32::try_from(fraction.len()).ok()?)?;
+    let power = Rational::from_integer(BigInt::from(10u8).pow(exponent.unsigned_abs()));
+    if exponent >= 0 {
+        value *= power;
+    } else {
+        value /= power;
+    }
+    Some(if negative { -value } else { value })
+}
+
+/// Returns the fraction that the shortest decimal form of `value` is written as, like `1/1000000` for `1e-6`.<br>
+/// This is exact for the constants that are written in the source, which cannot be parsed from text
+pub fn from_f64(value: f64) -> Option<Rational> {
+    if !value.is_finite() {
+        return None;
+    }
+    parse_decimal(&format!("{value:e}"))
+}
+
+/// Rounds an exact fraction to the nearest float
+pub fn to_f64(value: &Rational) -> f64 {
+    value.to_f64().unwrap_or(f64::NAN)
+}
+
+/// Formats a fraction as `numerator/denominator`, like `63360/1`
+pub fn to_fraction_string(value: &Rational) -> String {
+    format!("{}/{}", value.numer(), value.denom())
+}
+
+/// Formats a fraction exactly: as a decimal if it ends, like `1.609344` or `-40`, and as `numerator/denominator` otherwise, like `160/9`
+pub fn to_exact_string(value: &Rational) -> String {
+    // a fraction in lowest terms ends as a decimal if its denominator has no prime factors other than 2 and 5
+    let (mut rest, mut twos, mut fives) = (value.denom().clone(), 0usize, 0usize);
+    let (two, five) = (BigInt::from(2u8), BigInt::from(5u8));
+    while rest.is_multiple_of(&two) {
+        rest /= &two;
+        twos += 1;
+    }
+    while rest.is_multiple_of(&five) {
+        rest /= &five;
+        fives += 1;
+    }
+    if !rest.is_one() {
+        return format!("{}/{}", value.numer(), value.denom());
+    }
+    let places = twos.max(fives);
+    let digits = (value.abs() * Rational::from_integer(BigInt::from(10u8).pow(places as u32))).to_integer().to_string();
+    let sign = if value.is_negative() { "-" } else { "" };
+    match places {
+        0 => format!("{sign}{digits}"),
+        _ => {
+            let digits = format!("{digits:0>width$}", width = places + 1);
+            let (whole, fraction) = digits.split_at(digits.len() - places);
+            format!("{sign}{whole}.{fraction}")
+        }
+    }
+}
@@ -0,0 +1,233 @@
+use crate::error::ConversionError;
+use crate::formula::Formula;
+use crate::rational::Rational;
+use num_bigint::BigInt;
+use num_integer::Integer;
+use num_traits::{One, Signed, ToPrimitive, Zero};
+use serde::Serialize;
+use std::fmt;
+
+/// The arrows that can separate the reactants of a reaction from its products, in the order they are looked for
+const ARROWS: [&str; 5] = ["->", "→", "=>", "⇌", "="];
+
+/// One reactant or product of a reaction
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Species {
+    /// The formula as it was written, like `H2O`
+    pub text: String,
+    pub formula: Formula,
+    /// How many of it take part in the reaction. Until the reaction is balanced, this is the coefficient that was written, or 1
+    pub coefficient: u32,
+    /// Whether the coefficient was written in front of the formula, rather than left to the balancing
+    #[serde(skip)]
+    pub written: bool,
+}
+
+/// A chemical reaction, like `C3H8 + 5 O2 → 3 CO2 + 4 H2O`
+#[derive(Clone, Debug, PartialEq, Eq, Serialize)]
+pub struct Reaction {
+    pub reactants: Vec<Species>,
+    pub products: Vec<Species>,
+}
+
+impl Reaction {
+    /// Parses a reaction like `C3H8 + O2 -> CO2 + H2O`, with `->`, `→`, `=>`, `⇌` or `=` between the reactants and the products.<br>
+    /// A `+` that is followed by a formula separates two species, while any other `+` is part of a charge, like in `NH4+ + OH-`.
+    /// Electrons can be written as `e-`. `resolve_element` turns each symbol into its atomic number, like for [`Formula::parse`]
+    pub fn parse(reaction: &str, resolve_element: impl Fn(&str) -> Option<usize>) -> Result<Reaction, ConversionError> {
+        let invalid = |reason: &str| ConversionError::InvalidReaction { reason: reason.to_string() };
+        let (reactants, products) = ARROWS.iter()
+            .find_map(|arrow| reaction.split_once(arrow))
+            .ok_or_else(|| invalid("the reactants and the products must be separated by an arrow, like '->'"))?;
+        let parse_side = |side: &str| -> Result<Vec<Species>, ConversionError> {
+            split_species(side).into_iter().map(|text| parse_species(text, &resolve_element)).collect()
+        };
+        let reaction = Reaction { reactants: parse_side(reactants)?, products: parse_side(products)? };
+        if reaction.reactants.is_empty() || reaction.products.is_empty() {
+            return Err(invalid("there must be at least one reactant and one product"));
+        }
+        Ok(reaction)
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reactions only contain types that serialize to JSON")
+    }
+
+    /// Returns every reactant and then every product
+    pub fn species(&self) -> impl Iterator<Item = &Species> {
+        self.reactants.iter().chain(&self.products)
+    }
+
+    /// Sets the coefficients to the smallest whole numbers that conserve every element and the charge.<br>
+    /// Each element gives one equation, with the reactants counted as positive and the products as negative,
+    /// and the coefficients are the vector that every equation sends to 0. Fails if there is no such vector with every
+    /// coefficient above 0, or if there is more than one, which means that the reaction is several reactions at once.<br>
+    /// If any coefficients were written, they are kept, and the others are scaled to match them, but only if they balance the reaction too
+    pub fn balance(&mut self) -> Result<(), ConversionError> {
+        let species: Vec<&Species> = self.species().collect();
+        let sides: Vec<i64> = self.reactants.iter().map(|_| 1).chain(self.products.iter().map(|_| -1)).collect();
+        let mut atomic_numbers: Vec<usize> = Vec::new();
+        for (atomic_number, _) in species.iter().flat_map(|species| &species.formula.elements) {
+            if !atomic_numbers.contains(atomic_number) {
+                atomic_numbers.push(*atomic_number);
+            }
+        }
+        let mut matrix: Vec<Vec<Rational>> = atomic_numbers.iter().map(|atomic_number| {
+            species.iter().zip(&sides).map(|(species, side)| {
+                let atoms = species.formula.elements.iter()
+                    .find(|(existing, _)| existing == atomic_number)
+                    .map_or(0, |(_, atoms)| i64::from(*atoms));
+                Rational::from_integer(BigInt::from(atoms * side))
+            }).collect()
+        }).collect();
+        if species.iter().any(|species| species.formula.charge != 0) {
+            matrix.push(species.iter().zip(&sides)
+                .map(|(species, side)| Rational::from_integer(BigInt::from(i64::from(species.formula.charge) * side)))
+                .collect());
+        }
+        let cannot_balance = |reason: String| ConversionError::CannotBalance { reaction: self.to_string(), reason };
+        let vector = match null_vector(matrix, species.len()) {
+            Ok(vector) => vector,
+            Err(0) => return Err(cannot_balance("no coefficients conserve every element and the charge".to_string())),
+            Err(ways) => return Err(cannot_balance(format!("it can be balanced in {ways} independent ways, so it is several reactions at once"))),
+        };
+        let coefficients = smallest_integers(&vector);
+        let mut balanced = Vec::new();
+        for (species, coefficient) in species.iter().zip(coefficients) {
+            if coefficient.is_zero() {
+                return Err(cannot_balance(format!("{} would need a coefficient of 0", species.text)));
+            }
+            if coefficient.is_negative() {
+                return Err(cannot_balance(format!("{} would have to be on the other side", species.text)));
+            }
+            balanced.push(coefficient.to_u32().ok_or_else(|| cannot_balance("the coefficients are too large".to_string()))?);
+        }
+        if let Some(first) = species.iter().position(|species| species.written) {
+            // the written coefficients balance the reaction if they are the same multiple of the smallest ones,
+            // and the coefficients that are not written are filled in with that multiple too
+            let (written, smallest) = (u64::from(species[first].coefficient), u64::from(balanced[first]));
+            let proportional = species.iter().zip(&balanced)
+                .filter(|(each, _)| each.written)
+                .all(|(each, coefficient)| u64::from(each.coefficient) * smallest == u64::from(*coefficient) * written);
+            let mut expected = self.clone();
+            expected.set_coefficients(balanced.clone());
+            if !proportional {
+                return Err(cannot_balance(format!("the coefficients that are written do not conserve every element and the charge, unlike {expected}")));
+            }
+            let mut filled = Vec::new();
+            for (each, coefficient) in species.iter().zip(&balanced) {
+                let scaled = u64::from(*coefficient) * written;
+                filled.push(match each.written {
+                    true => each.coefficient,
+                    false if scaled % smallest == 0 => u32::try_from(scaled / smallest)
+                        .map_err(|_| cannot_balance("the coefficients are too large".to_string()))?,
+                    false => return Err(cannot_balance(format!("{} would need a coefficient that is not whole to match the ones that are written, unlike {expected}", each.text))),
+                });
+            }
+            balanced = filled;
+        }
+        self.set_coefficients(balanced);
+        Ok(())
+    }
+
+    fn set_coefficients(&mut self, coefficients: Vec<u32>) {
+        for (species, coefficient) in self.reactants.iter_mut().chain(self.products.iter_mut()).zip(coefficients) {
+            species.coefficient = coefficient;
+        }
+    }
+}
+
+impl fmt::Display for Reaction {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let side = |species: &[Species]| species.iter()
+            .map(|species| match species.coefficient {
+                1 => species.text.clone(),
+                coefficient => format!("{coefficient} {}", species.text),
+            })
+            .collect::<Vec<_>>()
+            .join(" + ");
+        write!(f, "{} → {}", side(&self.reactants), side(&self.products))
+    }
+}
+
+/// Splits one side of a reaction at every `+` that is followed by the start of another species
+fn split_species(side: &str) -> Vec<&str> {
+    let mut species = Vec::new();
+    let mut start = 0;
+    for (index, c) in side.char_indices() {
+        if c != '+' || side[..index].ends_with('^') {
+            continue;
+        }
+        let next = side[index + 1..].trim_start().chars().next();
+        if next.is_some_and(|next| next.is_ascii_uppercase() || next.is_ascii_digit() || next == '(' || next == 'e') {
+            species.push(side[start..index].trim());
+            start = index + 1;
+        }
+    }
+    species.push(side[start..].trim());
+    species.retain(|species| !species.is_empty());
+    species
+}
+
+/// Parses a species like `H2O` or `2 H2O`, keeping the coefficient that is written in front of it
+fn parse_species(text: &str, resolve_element: &impl Fn(&str) -> Option<usize>) -> Result<Species, ConversionError> {
+    let digits = text.len() - text.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+    let coefficient = match digits {
+        0 => 1,
+        _ => text[..digits].parse::<u32>().ok().filter(|coefficient| *coefficient > 0)
+            .ok_or_else(|| ConversionError::InvalidReaction { reason: format!("'{}' is not a valid coefficient", &text[..digits]) })?,
+    };
+    let text = text[digits..].trim();
+    let formula = match text {
+        "e-" | "e⁻" | "e^-" => Formula { elements: Vec::new(), charge: -1, written: "e⁻".to_string() },
+        _ => Formula::parse(text, resolve_element)?,
+    };
+    Ok(Species { text: text.to_string(), formula, coefficient, written: digits > 0 })
+}
+
+/// Reduces `matrix` to row echelon form and returns the vector that every row sends to 0, scaled so that one entry is 1.<br>
+/// Returns how many independent vectors there are instead if that is not exactly one
+fn null_vector(mut matrix: Vec<Vec<Rational>>, columns: usize) -> Result<Vec<Rational>, usize> {
+    let mut pivots: Vec<usize> = Vec::new();
+    for column in 0..columns {
+        let row = pivots.len();
+        let Some(pivot) = (row..matrix.len()).find(|candidate| !matrix[*candidate][column].is_zero()) else {
+            continue;
+        };
+        matrix.swap(row, pivot);
+        let scale = matrix[row][column].recip();
+        let pivot_row: Vec<Rational> = matrix[row].iter().map(|value| value * &scale).collect();
+        for (other, values) in matrix.iter_mut().enumerate() {
+            if other != row && !values[column].is_zero() {
+                let factor = values[column].clone();
+                for (value, pivot_value) in values.iter_mut().zip(&pivot_row) {
+                    *value -= pivot_value * &factor;
+                }
+            }
+        }
+        matrix[row] = pivot_row;
+        pivots.push(column);
+    }
+    let free: Vec<usize> = (0..columns).filter(|column| !pivots.contains(column)).collect();
+    if free.len() != 1 {
+        return Err(free.len());
+    }
+    let mut vector = vec![Rational::zero(); columns];
+    vector[free[0]] = Rational::one();
+    for (row, column) in pivots.iter().enumerate() {
+        vector[*column] = -matrix[row][free[0]].clone();
+    }
+    Ok(vector)
+}
+
+/// Scales a vector of fractions to the smallest whole numbers in the same ratio, with the first one that is not 0 above 0
+fn smallest_integers(vector: &[Rational]) -> Vec<BigInt> {
+    let denominators = vector.iter().fold(BigInt::one(), |lcm, value| lcm.lcm(value.denom()));
+    let integers: Vec<BigInt> = vector.iter().map(|value| (value * &denominators).to_integer()).collect();
+    let divisor = integers.iter().fold(BigInt::zero(), |gcd, value| gcd.gcd(value));
+    let sign = match integers.iter().find(|value| !value.is_zero()).is_some_and(Signed::is_negative) {
+        true => -BigInt::one(),
+        false => BigInt::one(),
+    };
+    integers.into_iter().map(|value| value / &divisor * &sign).collect()
+}
@@ -0,0 +1,1383 @@
+use crate::algorithm::{self, Cost, Edge};
+use crate::error::ConversionError;
+use crate::formula::Formula;
+use crate::reaction::Reaction;
+use crate::prefixes::{self, Prefix, PrefixSet};
+use crate::rational::{self, Rational, RationalConversion};
+use crate::significant_figures;
+use crate::report::{ConversionReport, ElementAmount, ElementShare, EmpiricalReport, MolarMassReport, Report, StepReport, UnitsReport};
+use crate::parsing::{extract_chemicals, extract_composition, extract_dimension, extract_uncertainty, extract_unit, extract_value_and_units,
+    restore_chemicals, split_chemical_index, split_reaction, subscript_number, terminate};
+use crate::structs::{Composition, Conversion, ConversionResult, Dimension, Element, IDGenerator, Inconsistency, LimitingReagent, Query, Step, Unit};
+use std::{collections::{HashMap, HashSet}, fs, path::Path};
+use num_traits::Zero;
+
+const ERR_ID_UNDEFINED: &str = "UnitIDs HashMap is missing a definition for an ID";
+const AVAGADROS_CONSTANT: f64 = 6.02214076e23;
+/// How far apart two factors that should be identical can drift from rounding alone
+const FLOAT_TOLERANCE: f64 = 1e-12;
+/// Aliases of the generic units that are swapped for substance-specific units when a chemical is given
+const GRAMS_ALIAS: &str = "g";
+const MOLES_ALIAS: &str = "mol";
+const PARTICLES_ALIAS: &str = "particle";
+/// The largest number that the mole ratios of a composition are multiplied by to make them whole numbers
+const MAX_RATIO_MULTIPLIER: u32 = 12;
+/// The most atoms of one element that an empirical formula can have, and the largest multiple of it that a molecular formula can be
+const MAX_FORMULA_COUNT: u32 = 10_000;
+
+/// Owns every unit, conversion and element that the engine knows about
+pub struct Registry {
+    units_generator: IDGenerator,
+    unit_ids: HashMap<usize, Unit>,
+    unit_aliases: HashMap<String, usize>,
+    element_ids: HashMap<usize, Element>,
+    element_aliases: HashMap<String, usize>,
+    substances: HashMap<String, Substance>,
+    /// The prefixed units that have been created so far, keyed by the unprefixed unit and the prefix's name
+    prefixed_units: HashMap<(usize, &'static str), usize>,
+    /// The `$` line that linked each pair of units, keyed by the lower id first
+    definitions: HashMap<(usize, usize), String>,
+    /// The units that are connected to each base unit, including the base itself
+    components: HashMap<usize, Vec<usize>>,
+}
+
+/// The grams, moles and particles units that were created for one chemical
+#[derive(Clone, Copy)]
+struct Substance {
+    grams: usize,
+    moles: usize,
+    particles: usize,
+}
+
+/// The mass of one mole of a chemical, summed from the masses of its elements
+struct MolarMass {
+    grams: f64,
+    /// The same mass as an exact fraction, if every element's mass is a plain decimal
+    exact_grams: Option<Rational>,
+    /// How far off the mass may be, with the uncertainties of different elements treated as independent
+    uncertainty: f64,
+}
+
+impl MolarMass {
+    /// Returns the exact mass rounded once to a float, so that it prints like `74.092` instead of with the error of summing floats
+    fn closest_grams(&self) -> f64 {
+        self.exact_grams.as_ref().map_or(self.grams, rational::to_f64)
+    }
+}
+
+/// Units that were broken down into units that are not products of other units, and the steps that did so
+struct Decomposition {
+    steps: Vec<Step>,
+    numers: Vec<usize>,
+    denoms: Vec<usize>,
+}
+
+impl Default for Registry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Registry {
+    /// Creates a registry with no units or elements
+    pub fn new() -> Self {
+        Registry {
+            units_generator: IDGenerator::new(0),
+            unit_ids: HashMap::new(),
+            unit_aliases: HashMap::new(),
+            element_ids: HashMap::new(),
+            element_aliases: HashMap::new(),
+            substances: HashMap::new(),
+            prefixed_units: HashMap::new(),
+            definitions: HashMap::new(),
+            components: HashMap::new(),
+        }
+    }
+
+    /// Removes every unit, conversion and element
+    pub fn clear(&mut self) {
+        self.units_generator.clear();
+        self.unit_ids.clear();
+        self.unit_aliases.clear();
+        self.element_ids.clear();
+        self.element_aliases.clear();
+        self.substances.clear();
+        self.prefixed_units.clear();
+        self.definitions.clear();
+        self.components.clear();
+    }
+
+    pub fn unit(&self, id: usize) -> Option<&Unit> {
+        self.unit_ids.get(&id)
+    }
+
+    pub fn unit_ids(&self) -> &HashMap<usize, Unit> {
+        &self.unit_ids
+    }
+
+    /// Looks up the id of the unit that has the given alias
+    pub fn unit_id(&self, alias: &str) -> Option<usize> {
+        self.unit_aliases.get(alias).copied()
+    }
+
+    /// Looks up the id of the unit that has the given alias, or of a prefixed version of a unit, like `km`.<br>
+    /// Prefixed units are only created the first time that they are used
+    pub fn resolve_unit(&mut self, alias: &str) -> Option<usize> {
+        if let Some(id) = self.unit_id(alias) {
+            return Some(id);
+        }
+        for (prefix, rest) in prefixes::split_prefix(alias) {
+            let base = match self.unit_id(rest) {
+                None => continue,
+                Some(base) => base
+            };
+            let accepts_prefix = self.unit_ids.get(&base).expect(ERR_ID_UNDEFINED).get_prefixes()
+                .is_some_and(|prefixes| prefixes.contains(prefix));
+            if accepts_prefix {
+                return Some(self.prefixed_unit(prefix, base));
+            }
+        }
+        None
+    }
+
+    /// Returns the unit that is `base` with `prefix` in front of it, creating it if it does not exist yet
+    fn prefixed_unit(&mut self, prefix: &'static Prefix, base: usize) -> usize {
+        if let Some(id) = self.prefixed_units.get(&(base, prefix.name)) {
+            return *id;
+        }
+        let base_unit = self.unit_ids.get(&base).expect(ERR_ID_UNDEFINED);
+        let mut unit = Unit::new(format!("{}{}", prefix.name, base_unit.get_name()), &mut self.units_generator);
+        if let Some(dimension) = base_unit.get_dimension() {
+            unit.set_dimension(dimension);
+        }
+        let id = self.insert_unit(unit);
+        let (numerator, denominator) = prefix.fraction();
+        let conversion = Conversion::new(numerator, denominator).exact()
+            .with_rational(Some(RationalConversion::scale(prefix.exact_fraction())));
+        self.link(id, base, conversion)
+            .expect("A new unit cannot conflict with any existing conversion");
+        self.prefixed_units.insert((base, prefix.name), id);
+        id
+    }
+
+    /// Returns every registered unit in the order that they were created
+    pub fn units(&self) -> impl Iterator<Item = &Unit> {
+        (0..self.units_generator.peek()).map(|id| self.unit_ids.get(&id).expect(ERR_ID_UNDEFINED))
+    }
+
+    pub fn element(&self, atomic_number: usize) -> Option<&Element> {
+        self.element_ids.get(&atomic_number)
+    }
+
+    /// Returns the element with this symbol or name, like `Fe` or `Iron`
+    pub fn element_by_alias(&self, alias: &str) -> Option<&Element> {
+        self.element_aliases.get(alias).and_then(|atomic_number| self.element_ids.get(atomic_number))
+    }
+
+    /// Returns every registered element in order of atomic number
+    pub fn elements(&self) -> impl Iterator<Item = &Element> {
+        let mut elements: Vec<&Element> = self.element_ids.values().collect();
+        elements.sort_by_key(|element| element.atomic_number);
+        elements.into_iter()
+    }
+
+    /// Registers a unit from a list of aliases, like `meter|s, m`.<br>
+    /// The aliases may be followed by the dimension of the unit, like `meter|s, m : length`,
+    /// and by `prefixable` or `binary prefixable` to accept prefixes like kilo- or kibi-.<br>
+    /// A leading `#` is allowed. Returns the id of the new unit
+    pub fn define_unit(&mut self, definition: &str) -> Result<usize, ConversionError> {
+        fn push_word_to_names(move_next_word_up: bool, names: &mut Vec<String>, word: &str) {
+            if move_next_word_up {
+                names.insert(names.len() - 1, word.to_string());
+            } else {
+                names.push(word.to_string());
+            }
+        }
+        let line = terminate(definition);
+        let line = line.strip_prefix('#').unwrap_or(&line);
+        let (line, attributes) = match line.split_once(':') {
+            None => (line.to_string(), ""),
+            Some((aliases, attributes)) => (terminate(aliases), attributes.trim_end_matches(';'))
+        };
+        let mut dimension = None;
+        let mut prefixes = None;
+        for attribute in attributes.split(',').map(str::trim).filter(|attribute| !attribute.is_empty()) {
+            match attribute {
+                "prefixable" => prefixes = Some(PrefixSet::Metric),
+                "binary prefixable" => prefixes = Some(PrefixSet::Binary),
+                _ => dimension = Some(extract_dimension(attribute)?)
+            }
+        }
+        let mut names: Vec<String> = Vec::new();
+        let mut word = String::new();
+        let mut in_alias = false;
+        let mut move_next_word_up = false;
+        for c in line.chars() {
+            if c.is_ascii_digit() {
+                return Err(ConversionError::malformed(format!("Cannot create unit with a digit ({c}) in its name")));
+            }
+            if !in_alias {
+                // waiting for a unit alias to begin, which may also be a symbol like `°`
+                if c.is_alphabetic() || !(c.is_ascii() || c.is_whitespace()) {
+                    word.push(c);
+                    in_alias = true;
+                }
+                continue;
+            }
+            // waiting for a unit alias to end
+            match c {
+                '|' => {
+                    names.push(word.clone());
+                    move_next_word_up = true;
+                },
+                ',' => {
+                    push_word_to_names(move_next_word_up, &mut names, &word);
+                    word.clear();
+                    in_alias = false;
+                },
+                ';' => {
+                    break;
+                }
+                _ => {
+                    word.push(c);
+                }
+            };
+        }
+        if !word.is_empty() {
+            push_word_to_names(move_next_word_up, &mut names, &word);
+        }
+
+        let name = match names.first() {
+            None => return Err(ConversionError::malformed("Unit definition must contain at least one alias")),
+            Some(thing) => thing
+        };
+        let mut unit = Unit::new(name.clone(), &mut self.units_generator);
+        if let Some(dimension) = dimension {
+            unit.set_dimension(dimension);
+        }
+        if let Some(prefixes) = prefixes {
+            unit.set_prefixes(prefixes);
+        }
+        let id = self.insert_unit(unit);
+
+        for n in names.iter() {
+            self.unit_aliases.insert(n.to_string(), id);
+        }
+        Ok(id)
+    }
+
+    /// Registers a conversion factor from an equation, like `1 meter = 100 cm`.<br>
+    /// Scales with an offset are given by two points, like `0 celsius = 273.15 kelvin, 100 celsius = 373.15 kelvin`.<br>
+    /// The equation may be followed by how precise the factor is, either `: exact` or like `: precision 1e-6`,
+    /// which is the relative error of the factor. Conversions are found along the most precise path.<br>
+    /// A unit can also be defined as a product of other units, like `1 acre = 43560 ft^2`.<br>
+    /// A leading `$` is allowed. Returns the ids of the two units that were linked,
+    /// or the id of the defined unit twice if it was defined as a product
+    pub fn define_conversion(&mut self, definition: &str) -> Result<(usize, usize), ConversionError> {
+        let line = terminate(definition);
+        let line = line.strip_prefix('$').unwrap_or(&line).trim();
+        let (line, relative_error) = match line.split_once(':') {
+            None => (line, None),
+            Some((line, attribute)) => (line, Some(extract_precision(attribute.trim_end_matches(';').trim())?))
+        };
+        let (first, second) = match line.split_once(',') {
+            None => (line, None),
+            Some((first, second)) => (first, Some(second))
+        };
+        let (left, right) = self.parse_equation(first)?;
+        let (value_1, unit_1, value_2, unit_2) = match (single_unit(&left), single_unit(&right)) {
+            (Some(unit_1), Some(unit_2)) => (left.value, unit_1, right.value, unit_2),
+            (Some(unit), None) if second.is_none() => {
+                let relative_error = relative_error.unwrap_or(Conversion::DEFAULT_RELATIVE_ERROR);
+                return self.define_composition(unit, right.value / left.value, exact_ratio(&right, &left), relative_error, right)
+            },
+            (None, Some(unit)) if second.is_none() => {
+                let relative_error = relative_error.unwrap_or(Conversion::DEFAULT_RELATIVE_ERROR);
+                return self.define_composition(unit, left.value / right.value, exact_ratio(&left, &right), relative_error, left)
+            },
+            (None, None) => return Err(ConversionError::malformed("One side of a conversion must be a single unit")),
+            _ => return Err(ConversionError::malformed("Both points of an offset conversion must relate two single units"))
+        };
+        if unit_1 == unit_2 {
+            return Err(ConversionError::malformed("A conversion must link two different units"));
+        }
+        let one_to_two = match second {
+            None => {
+                if value_1 == 0f64 || value_2 == 0f64 {
+                    return Err(ConversionError::malformed("A conversion containing a zero needs a second point, like `$ 0 celsius = 273.15 kelvin, 100 celsius = 373.15 kelvin`"));
+                }
+                Conversion::new(value_2, value_1).with_rational(exact_ratio(&right, &left).map(RationalConversion::scale))
+            },
+            Some(second) => {
+                let (second_left, second_right) = self.parse_equation(second)?;
+                let (second_1, second_2) = match (single_unit(&second_left), single_unit(&second_right)) {
+                    (Some(a), Some(b)) if (a, b) == (unit_1, unit_2) => (second_left, second_right),
+                    (Some(b), Some(a)) if (a, b) == (unit_1, unit_2) => (second_right, second_left),
+                    _ => return Err(ConversionError::malformed("Both points of an offset conversion must relate the same two units"))
+                };
+                if second_1.value == value_1 || second_2.value == value_2 {
+                    return Err(ConversionError::malformed("The two points of an offset conversion must be different"));
+                }
+                let exact = match (&left.exact_value, &right.exact_value, &second_1.exact_value, &second_2.exact_value) {
+                    (Some(first_1), Some(first_2), Some(second_1), Some(second_2)) =>
+                        RationalConversion::through_points((first_1, first_2), (second_1, second_2)),
+                    _ => None
+                };
+                Conversion::through_points((value_1, value_2), (second_1.value, second_2.value)).with_rational(exact)
+            }
+        };
+        let dimension_1 = self.unit_ids.get(&unit_1).expect(ERR_ID_UNDEFINED).get_dimension();
+        let dimension_2 = self.unit_ids.get(&unit_2).expect(ERR_ID_UNDEFINED).get_dimension();
+        match (dimension_1, dimension_2) {
+            (Some(dimension_1), Some(dimension_2)) if dimension_1 != dimension_2 => {
+                return Err(ConversionError::ConflictingDimensions {
+                    first: self.unit_ids.get(&unit_1).expect(ERR_ID_UNDEFINED).get_name().to_string(),
+                    first_dimension: dimension_1,
+                    second: self.unit_ids.get(&unit_2).expect(ERR_ID_UNDEFINED).get_name().to_string(),
+                    second_dimension: dimension_2,
+                });
+            },
+            (Some(dimension), None) => self.spread_dimension(unit_2, dimension),
+            (None, Some(dimension)) => self.spread_dimension(unit_1, dimension),
+            _ => {}
+        }
+        let one_to_two = match relative_error {
+            None => one_to_two,
+            Some(relative_error) => one_to_two.with_relative_error(relative_error),
+        };
+        self.link(unit_1, unit_2, one_to_two)?;
+        self.definitions.insert((unit_1.min(unit_2), unit_1.max(unit_2)), definition.trim().to_string());
+        Ok((unit_1, unit_2))
+    }
+
+    /// Defines `unit` as `factor` times the units of `product`
+    fn define_composition(&mut self, unit: usize, factor: f64, exact_factor: Option<Rational>, relative_error: f64, product: Query) -> Result<(usize, usize), ConversionError> {
+        if product.starting_numers.contains(&unit) || product.starting_denoms.contains(&unit) {
+            return Err(ConversionError::malformed("A unit cannot be defined in terms of itself"));
+        }
+        if !factor.is_normal() {
+            return Err(ConversionError::malformed("A unit must be defined as a nonzero multiple of other units"));
+        }
+        let defined = self.unit_ids.get(&unit).expect(ERR_ID_UNDEFINED);
+        if defined.get_composition().is_some() {
+            return Err(ConversionError::malformed(format!("{} is already defined as a product of other units", defined.get_name())));
+        }
+        match (defined.get_dimension(), self.dimension_of(&product.starting_numers, &product.starting_denoms)?) {
+            (Some(declared), Some(derived)) if declared != derived => {
+                return Err(ConversionError::ConflictingDimensions {
+                    first: defined.get_name().to_string(),
+                    first_dimension: declared,
+                    second: self.units_to_string(&product.starting_numers, &product.starting_denoms),
+                    second_dimension: derived,
+                });
+            },
+            (None, Some(derived)) => self.spread_dimension(unit, derived),
+            _ => {}
+        }
+        self.unit_ids.get_mut(&unit).expect(ERR_ID_UNDEFINED).set_composition(Composition {
+            factor,
+            exact_factor,
+            relative_error,
+            numers: product.starting_numers,
+            denoms: product.starting_denoms,
+        });
+        Ok((unit, unit))
+    }
+
+    /// Gives `dimension` to `start` and to every unit connected to it that does not have a dimension yet
+    fn spread_dimension(&mut self, start: usize, dimension: Dimension) {
+        let mut stack = vec![start];
+        while let Some(id) = stack.pop() {
+            let unit = self.unit_ids.get_mut(&id).expect(ERR_ID_UNDEFINED);
+            if unit.get_dimension().is_some() {
+                continue;
+            }
+            unit.set_dimension(dimension);
+            stack.extend(unit.connected_ids());
+        }
+    }
+
+    /// Multiplies together the dimensions of the numerators and divides by the denominators.<br>
+    /// Returns `None` if any of the units has no known dimension, and an error if an exponent grows too large
+    pub fn dimension_of(&self, numers: &[usize], denoms: &[usize]) -> Result<Option<Dimension>, ConversionError> {
+        let mut dimension = Dimension::default();
+        let too_large = || ConversionError::ExponentOutOfRange { expression: self.powers_to_string(numers, denoms) };
+        for id in numers {
+            let Some(other) = self.unit_ids.get(id).and_then(Unit::get_dimension) else { return Ok(None) };
+            dimension = dimension.checked_mul(other).ok_or_else(too_large)?;
+        }
+        for id in denoms {
+            let Some(other) = self.unit_ids.get(id).and_then(Unit::get_dimension) else { return Ok(None) };
+            dimension = dimension.checked_div(other).ok_or_else(too_large)?;
+        }
+        Ok(Some(dimension))
+    }
+
+    /// Parses an equation, like `1 meter = 100 cm`, into one query for each side
+    fn parse_equation(&mut self, line: &str) -> Result<(Query, Query), ConversionError> {
+        let (left, right) = match line.split_once('=') {
+            None => return Err(ConversionError::malformed("Conversion must contain '=' to demonstrate equality")),
+            Some(thing) => thing
+        };
+        let left = extract_value_and_units(&terminate(left), &mut |alias| self.resolve_unit(alias))?;
+        let right = extract_value_and_units(&terminate(right), &mut |alias| self.resolve_unit(alias))?;
+        if left.starting_numers.is_empty() || right.starting_numers.is_empty() {
+            return Err(ConversionError::malformed("Each side of a conversion must contain a unit"));
+        }
+        Ok((left, right))
+    }
+
+    /// Registers a new unit as the base of its own component and returns its id
+    fn insert_unit(&mut self, unit: Unit) -> usize {
+        let id = unit.get_id();
+        unit.insert_into(&mut self.unit_ids);
+        self.components.insert(id, vec![id]);
+        id
+    }
+
+    /// Adds an edge in both directions between two units, and keeps the cached conversion of every unit to its base up to date.<br>
+    /// If the units are already connected, the new factor must agree with the one through their base
+    /// within the relative errors of the two, or nothing is linked
+    fn link(&mut self, id_1: usize, id_2: usize, one_to_two: Conversion) -> Result<(), ConversionError> {
+        let base_1 = self.unit_ids.get(&id_1).expect(ERR_ID_UNDEFINED).get_base();
+        let base_2 = self.unit_ids.get(&id_2).expect(ERR_ID_UNDEFINED).get_base();
+        let implied = (base_1 == base_2).then(|| self.conversion_between(id_1, id_2));
+        if let Some(implied) = &implied {
+            let deviation = (one_to_two.factor() / implied.factor() - 1f64).abs();
+            if deviation > one_to_two.relative_error() + implied.relative_error() + FLOAT_TOLERANCE {
+                return Err(ConversionError::InconsistentConversion {
+                    first: self.unit_ids.get(&id_1).expect(ERR_ID_UNDEFINED).get_name().to_string(),
+                    second: self.unit_ids.get(&id_2).expect(ERR_ID_UNDEFINED).get_name().to_string(),
+                    given: one_to_two.factor(),
+                    implied: implied.factor(),
+                });
+            }
+        }
+        let mut unit_1 = self.unit_ids.remove(&id_1).expect(ERR_ID_UNDEFINED);
+        let mut unit_2 = self.unit_ids.remove(&id_2).expect(ERR_ID_UNDEFINED);
+        unit_2.push_edge(&unit_1, one_to_two.inverse());
+        unit_1.push_edge(&unit_2, one_to_two.clone());
+        unit_1.insert_into(&mut self.unit_ids);
+        unit_2.insert_into(&mut self.unit_ids);
+        match implied {
+            None => self.merge(id_1, id_2, one_to_two),
+            Some(implied) if one_to_two.relative_error() < implied.relative_error() => self.rebase(base_1),
+            Some(_) => {}
+        }
+        Ok(())
+    }
+
+    /// Joins the components of two units that were just linked by moving every unit of the smaller component
+    /// onto the base of the larger one, so that each unit is only ever moved a logarithmic number of times
+    fn merge(&mut self, id_1: usize, id_2: usize, one_to_two: Conversion) {
+        let unit_1 = self.unit_ids.get(&id_1).expect(ERR_ID_UNDEFINED);
+        let unit_2 = self.unit_ids.get(&id_2).expect(ERR_ID_UNDEFINED);
+        let (base_1, base_2) = (unit_1.get_base(), unit_2.get_base());
+        let size = |base: usize| self.components.get(&base).map_or(0, Vec::len);
+        // `bridge` converts the base that is absorbed into the base that is kept
+        let (kept, absorbed, bridge) = match size(base_1) >= size(base_2) {
+            true => (base_1, base_2, unit_2.to_base().inverse().then(&one_to_two.inverse()).then(unit_1.to_base())),
+            false => (base_2, base_1, unit_1.to_base().inverse().then(&one_to_two).then(unit_2.to_base())),
+        };
+        let members = self.components.remove(&absorbed).unwrap_or_default();
+        for id in &members {
+            let unit = self.unit_ids.get_mut(id).expect(ERR_ID_UNDEFINED);
+            let to_base = unit.to_base().then(&bridge).normalized();
+            unit.set_base(kept, to_base);
+        }
+        self.components.entry(kept).or_default().extend(members);
+    }
+
+    /// Recaches the conversion of every unit in a component to its base along the most precise paths,
+    /// which is needed when a conversion that is more precise than the cached ones is added inside the component
+    fn rebase(&mut self, base: usize) {
+        let tree = algorithm::shortest_path_tree(base, |id| self.edges_of(id));
+        // every unit costs more than its parent, so this caches each parent before its children
+        let mut order: Vec<(usize, usize, Cost)> = tree.into_iter().map(|(id, (parent, cost))| (id, parent, cost)).collect();
+        order.sort_by_key(|(id, _, cost)| (*cost, *id));
+        for (id, parent, _) in order.into_iter().skip(1) {
+            let parent_to_base = self.unit_ids.get(&parent).expect(ERR_ID_UNDEFINED).to_base().clone();
+            let unit = self.unit_ids.get_mut(&id).expect(ERR_ID_UNDEFINED);
+            let to_base = unit.convert(parent).expect("A unit must convert to its parent in the tree")
+                .then(&parent_to_base).normalized();
+            unit.set_base(base, to_base);
+        }
+    }
+
+    /// Returns the conversion from one unit to another through their base. Both must be in the same component
+    fn conversion_between(&self, from: usize, to: usize) -> Conversion {
+        let from = self.unit_ids.get(&from).expect(ERR_ID_UNDEFINED);
+        let to = self.unit_ids.get(&to).expect(ERR_ID_UNDEFINED);
+        debug_assert!(from.get_base() == to.get_base());
+        from.to_base().then(&to.to_base().inverse()).normalized()
+    }
+
+    /// Finds the most precise path from `start` to every unit in its component.<br>
+    /// The cached conversions to the base are not used here, because a path through the base can be less precise than a direct one
+    fn paths_from(&self, start: usize) -> HashMap<usize, (usize, Cost)> {
+        algorithm::shortest_path_tree(start, |id| self.edges_of(id))
+    }
+
+    /// Returns the steps along a path of units, flipped if the units are in a denominator.<br>
+    /// The offsets are only kept when `absolute` is true, because a unit inside a compound is only ever scaled
+    fn path_steps(&self, path: &[usize], inverse: bool, absolute: bool) -> Vec<Step> {
+        path.windows(2).map(|pair| {
+            let (this_id, next_id) = (pair[0], pair[1]);
+            let conversion = self.unit_ids.get(&this_id).expect(ERR_ID_UNDEFINED)
+                .convert(next_id)
+                .expect("The path must go along units that can convert along the path");
+            let conversion = if absolute { conversion.clone() } else { conversion.scale_only() };
+            match inverse {
+                false => Step::of(&conversion, this_id, next_id),
+                true => Step::of(&conversion.inverse(), next_id, this_id),
+            }
+        }).collect()
+    }
+
+    /// Converts `value` from one unit expression to another, like `meter / s` to `ft / s`
+    pub fn convert(&mut self, value: f64, from: &str, to: &str) -> Result<ConversionResult, ConversionError> {
+        self.convert_line(&format!("{from} : {to}"), |query| {
+            query.value *= value;
+            query.exact_value = query.exact_value.take().zip(rational::from_f64(value)).map(|(exact_value, value)| exact_value * value);
+            query.uncertainty = query.uncertainty.map(|uncertainty| uncertainty * value.abs());
+        })
+    }
+
+    /// Converts a full expression, like `1.3 meter : feet` or `12 g [H2O] : mol`.<br>
+    /// Each grams, moles or particles unit can be followed by its own chemical, and a reaction between them can follow `using`,
+    /// like `12 g [C3H8] : g [CO2] using C3H8 + 5 O2 -> 3 CO2 + 4 H2O`
+    pub fn convert_expression(&mut self, line: &str) -> Result<ConversionResult, ConversionError> {
+        self.convert_line(line, |_| {})
+    }
+
+    /// Converts the answer of an earlier conversion further, given the rest of the expression that follows it, like `: inch`.<br>
+    /// The answer is used with every digit that was calculated and with the significant figures and uncertainty that it had,
+    /// so that it is only rounded once, at the end
+    pub fn convert_answer(&mut self, previous: &ConversionResult, rest: &str) -> Result<ConversionResult, ConversionError> {
+        let units = self.answer_with_units(previous, String::new());
+        self.convert_line(&format!("{}{rest}", units.trim_start()), |query| {
+            query.value *= previous.answer;
+            query.exact_value = query.exact_value.take().zip(previous.exact_answer.clone()).map(|(exact_value, answer)| exact_value * answer);
+            query.significant_figures = significant_figures::min(query.significant_figures, previous.significant_figures);
+            query.resolution = previous.significant_figures.map(|figures| significant_figures::resolution_of(previous.answer, figures));
+            query.uncertainty = previous.uncertainty;
+        })
+    }
+
+    /// Converts the amount of each of several reactants into the same product, like
+    /// `12 g [C3H8], 50 g [O2] : g [CO2] using C3H8 + 5 O2 -> 3 CO2 + 4 H2O`.<br>
+    /// The reactant that makes the least of the product is the limiting reagent
+    pub fn limiting_reagent(&mut self, line: &str) -> Result<LimitingReagent, ConversionError> {
+        let (amounts, rest) = line.split_once(':').ok_or(ConversionError::InvalidReaction {
+            reason: "the amounts of the reactants must be followed by ':' and the product".to_string(),
+        })?;
+        if split_reaction(rest).1.is_none() {
+            return Err(ConversionError::InvalidReaction { reason: "finding the limiting reagent needs a reaction after 'using'".to_string() });
+        }
+        let results = amounts.split(',')
+            .map(|amount| self.convert_expression(&format!("{amount} :{rest}")))
+            .collect::<Result<Vec<_>, _>>()?;
+        let limiting = results.iter().enumerate()
+            .min_by(|(_, a), (_, b)| a.answer.total_cmp(&b.answer))
+            .map_or(0, |(index, _)| index);
+        Ok(LimitingReagent { results, limiting })
+    }
+
+    /// Converts a full expression like [`convert_expression`](Self::convert_expression),
+    /// and describes the result or the error in a form that can be serialized to JSON
+    pub fn report_expression(&mut self, line: &str) -> Report {
+        let result = self.convert_expression(line);
+        Report::from(result.map(|result| self.report(&result)))
+    }
+
+    /// Describes a conversion with the names of its units instead of their ids
+    pub fn report(&self, result: &ConversionResult) -> ConversionReport {
+        let query = &result.query;
+        ConversionReport {
+            value: query.value,
+            value_uncertainty: query.uncertainty,
+            interval: query.interval,
+            starting_units: self.units_report(&query.starting_numers, &query.starting_denoms),
+            ending_units: self.units_report(&query.ending_numers, &query.ending_denoms),
+            steps: result.steps.iter().map(|step| StepReport {
+                top_value: step.top_value(),
+                top_units: self.unit_names(step.top_ids()),
+                bottom_value: step.bottom_value(),
+                bottom_units: self.unit_names(step.bottom_ids()),
+                offset: step.offset(),
+            }).collect(),
+            answer: result.answer,
+            answer_text: self.answer_to_string(result),
+            exact_answer: result.exact_answer.as_ref().map(rational::to_fraction_string),
+            significant_figures: result.significant_figures,
+            uncertainty: result.uncertainty,
+        }
+    }
+
+    fn units_report(&self, numers: &[usize], denoms: &[usize]) -> UnitsReport {
+        UnitsReport {
+            numerators: self.unit_names(numers),
+            denominators: self.unit_names(denoms),
+        }
+    }
+
+    fn unit_names(&self, ids: &[usize]) -> Vec<String> {
+        ids.iter()
+            .map(|id| self.unit_ids.get(id).expect(ERR_ID_UNDEFINED).get_name().to_string())
+            .collect()
+    }
+
+    /// Parses and converts an expression, after `measure` has filled in the value that it starts with
+    fn convert_line(&mut self, line: &str, measure: impl FnOnce(&mut Query)) -> Result<ConversionResult, ConversionError> {
+        let (line, reaction) = split_reaction(line);
+        let (line, chemicals) = extract_chemicals(&terminate(line))?;
+        let mut substances = Vec::new();
+        for chemical in &chemicals {
+            let formula = Formula::parse(chemical, |symbol| self.element_aliases.get(symbol).copied())?;
+            substances.push(self.insert_formula(&formula)?);
+        }
+        let mut query = extract_value_and_units(&line, &mut |alias| self.resolve_chemical_unit(alias, &substances))
+            .map_err(|err| match err {
+                ConversionError::UnknownUnit { alias } => ConversionError::UnknownUnit { alias: restore_chemicals(&alias, &chemicals) },
+                err => err,
+            })?;
+        measure(&mut query);
+        // units without a chemical of their own take the only one that was named, like the moles of `12 g [H2O] : mol`
+        if let Some(substance) = substances.first().filter(|first| substances.iter().all(|substance| substance.moles == first.moles)) {
+            self.substitute_substance(&mut query, *substance);
+        }
+        match reaction {
+            None => self.convert_query(query),
+            Some(reaction) => {
+                let reaction = self.balance(reaction)?;
+                self.convert_through_reaction(query, &reaction)
+            }
+        }
+    }
+
+    /// Resolves an alias that is followed by the index of a chemical, like `g [0]`, into the unit of that chemical,
+    /// or any other alias into its unit. Only grams, moles and particles have a unit for each chemical
+    fn resolve_chemical_unit(&mut self, alias: &str, substances: &[Substance]) -> Option<usize> {
+        let Some((unit, index)) = split_chemical_index(alias) else {
+            return self.resolve_unit(alias);
+        };
+        let generic = self.resolve_unit(&unit)?;
+        let substance = substances.get(index)?;
+        self.substance_units(*substance).into_iter().find(|(existing, _)| *existing == generic).map(|(_, id)| id)
+    }
+
+    /// Converts an amount of one substance of a reaction into an amount of another, like grams of propane into grams of carbon dioxide.<br>
+    /// The starting units are converted into moles of their substance, multiplied by the ratio of the two coefficients, and then
+    /// converted into the ending units. The ratio is a step of its own rather than a conversion between the two moles units,
+    /// since it only holds for this reaction and would make any other reaction between the same substances inconsistent
+    fn convert_through_reaction(&mut self, query: Query, reaction: &Reaction) -> Result<ConversionResult, ConversionError> {
+        let mut coefficients = Vec::new();
+        // electrons have no elements, and so no molar mass to give them units
+        for species in reaction.species().filter(|species| !species.formula.elements.is_empty()) {
+            coefficients.push((self.insert_formula(&species.formula)?.moles, species.coefficient));
+        }
+        // the substances are either both multiplied, like `g [C3H8] : g [CO2]`, or both divided by, like `L / g [C3H8] : L / g [CO2]`
+        let (in_denoms, start, from, to) = match (self.find_substance(&query.starting_numers), self.find_substance(&query.ending_numers)) {
+            (Some((start, from)), Some((_, to))) => (false, start, from, to),
+            _ => match (self.find_substance(&query.starting_denoms), self.find_substance(&query.ending_denoms)) {
+                (Some((start, from)), Some((_, to))) => (true, start, from, to),
+                _ => return self.convert_query(query),
+            }
+        };
+        if from.moles == to.moles {
+            return self.convert_query(query);
+        }
+        let coefficient = |substance: Substance| coefficients.iter()
+            .find(|(moles, _)| *moles == substance.moles)
+            .map(|(_, coefficient)| *coefficient)
+            .ok_or_else(|| ConversionError::NotInReaction { substance: self.substance_name(substance), reaction: reaction.to_string() });
+        let ratio = Conversion::new(f64::from(coefficient(to)?), f64::from(coefficient(from)?)).exact();
+        let mut to_moles = Query {
+            ending_numers: query.starting_numers.clone(),
+            ending_denoms: query.starting_denoms.clone(),
+            ..query.clone()
+        };
+        match in_denoms {
+            false => to_moles.ending_numers[start] = from.moles,
+            true => to_moles.ending_denoms[start] = from.moles,
+        }
+        let mut from_moles = Query {
+            starting_numers: to_moles.ending_numers.clone(),
+            starting_denoms: to_moles.ending_denoms.clone(),
+            ..query.clone()
+        };
+        match in_denoms {
+            false => from_moles.starting_numers[start] = to.moles,
+            true => from_moles.starting_denoms[start] = to.moles,
+        }
+        let mut steps = self.convert_query(to_moles)?.steps;
+        // dividing by moles of one substance turns into dividing by moles of the other with the ratio upside down
+        steps.push(match in_denoms {
+            false => Step::of(&ratio, from.moles, to.moles),
+            true => Step::of(&ratio.inverse(), to.moles, from.moles),
+        });
+        steps.extend(self.convert_query(from_moles)?.steps);
+        Ok(self.apply_steps(query, steps))
+    }
+
+    /// Returns the index of the first unit that belongs to a chemical, and the chemical
+    fn find_substance(&self, ids: &[usize]) -> Option<(usize, Substance)> {
+        ids.iter().enumerate().find_map(|(index, id)| self.substances.values()
+            .find(|substance| [substance.grams, substance.moles, substance.particles].contains(id))
+            .map(|substance| (index, *substance)))
+    }
+
+    fn substance_name(&self, substance: Substance) -> String {
+        self.substances.iter()
+            .find(|(_, existing)| existing.moles == substance.moles)
+            .map_or_else(String::new, |(name, _)| name.clone())
+    }
+
+    /// Breaks down the molar mass of a chemical, like `Ca(OH)2`, into the mass and mass percent of each element
+    pub fn molar_mass(&self, formula: &str) -> Result<MolarMassReport, ConversionError> {
+        let formula = Formula::parse(formula, |symbol| self.element_aliases.get(symbol).copied())?;
+        let (molar_mass, _) = self.find_mm_and_name(&formula.elements)?;
+        let total = molar_mass.closest_grams();
+        let elements = formula.elements.iter().map(|(atomic_number, count)| {
+            let element = self.element_ids.get(atomic_number).expect("find_mm_and_name has checked every element");
+            // multiplying the decimal exactly keeps the mass as short as the weight, like 3.024 for 3 × 1.008
+            let mass = rational::from_f64(element.molar_mass)
+                .map_or(element.molar_mass * f64::from(*count), |weight| rational::to_f64(&(weight * Rational::from_integer((*count).into()))));
+            ElementShare {
+                symbol: element.symbol.clone(),
+                name: element.name.clone(),
+                atomic_number: *atomic_number,
+                count: *count,
+                atomic_weight: element.molar_mass,
+                standard_weight: element.standard_weight,
+                mass,
+                mass_percent: 100f64 * mass / total,
+            }
+        }).collect();
+        Ok(MolarMassReport {
+            formula: formula.written,
+            charge: formula.charge,
+            elements,
+            molar_mass: total,
+            uncertainty: molar_mass.uncertainty,
+        })
+    }
+
+    /// Finds the empirical formula of a compound from the amount of each element in it, like `C 40.0%, H 6.7%, O 53.3%`,
+    /// and its molecular formula if its molar mass follows, like `: 180.16 g/mol`.<br>
+    /// The mole ratios are multiplied by the smallest number that brings every one of them within `tolerance` of a whole number
+    pub fn empirical_formula(&self, composition: &str, tolerance: f64) -> Result<EmpiricalReport, ConversionError> {
+        let (amounts, molar_mass) = extract_composition(composition)?;
+        let mut elements = Vec::new();
+        for (alias, position, grams, significant_figures) in amounts {
+            let element = self.element_by_alias(&alias).ok_or(ConversionError::UnknownElement { symbol: alias, position })?;
+            if elements.iter().any(|(atomic_number, _): &(usize, ElementAmount)| *atomic_number == element.atomic_number) {
+                return Err(ConversionError::InvalidComposition { reason: format!("{} appears more than once", element.symbol) });
+            }
+            elements.push((element.atomic_number, ElementAmount {
+                symbol: element.symbol.clone(),
+                atomic_weight: element.molar_mass,
+                grams,
+                significant_figures,
+                moles: grams / element.molar_mass,
+                ratio: 0f64,
+                count: 0,
+            }));
+        }
+        let fewest = elements.iter().map(|(_, amount)| amount.moles).fold(f64::INFINITY, f64::min);
+        for (_, amount) in elements.iter_mut() {
+            amount.ratio = amount.moles / fewest;
+        }
+        let is_whole = |value: f64| (value - value.round()).abs() <= tolerance;
+        let no_whole_ratio = || ConversionError::NoWholeNumberRatio { ratios: elements.iter().map(|(_, amount)| amount.ratio).collect() };
+        let multiplier = (1..=MAX_RATIO_MULTIPLIER)
+            .find(|multiplier| elements.iter().all(|(_, amount)| is_whole(amount.ratio * f64::from(*multiplier))))
+            .ok_or_else(no_whole_ratio)?;
+        // every large enough float is whole, so a ratio like 1e300 would pass the search above without meaning anything
+        if !elements.iter().all(|(_, amount)| amount.ratio * f64::from(multiplier) <= f64::from(MAX_FORMULA_COUNT)) {
+            return Err(no_whole_ratio());
+        }
+        let mut counts = Vec::new();
+        for (atomic_number, amount) in elements.iter_mut() {
+            amount.count = (amount.ratio * f64::from(multiplier)).round() as u32;
+            counts.push((*atomic_number, amount.count));
+        }
+        let (empirical_molar_mass, empirical_formula) = self.find_mm_and_name(&counts)?;
+        let empirical_molar_mass = empirical_molar_mass.closest_grams();
+        let molecular_multiple = match molar_mass {
+            None => None,
+            Some(molar_mass) => match molar_mass / empirical_molar_mass {
+                multiple if multiple.round() >= 1f64 && multiple <= f64::from(MAX_FORMULA_COUNT) && is_whole(multiple) => Some(multiple.round() as u32),
+                _ => return Err(ConversionError::MolarMassNotMultiple { molar_mass, empirical_molar_mass }),
+            }
+        };
+        let molecular_formula = match molecular_multiple {
+            None => None,
+            Some(multiple) => {
+                let counts: Vec<(usize, u32)> = counts.iter().map(|(atomic_number, count)| (*atomic_number, count * multiple)).collect();
+                Some(self.find_mm_and_name(&counts)?.1)
+            }
+        };
+        Ok(EmpiricalReport {
+            elements: elements.into_iter().map(|(_, amount)| amount).collect(),
+            multiplier,
+            empirical_formula,
+            empirical_molar_mass,
+            molar_mass,
+            molecular_multiple,
+            molecular_formula,
+        })
+    }
+
+    /// Parses a reaction like `C3H8 + O2 -> CO2 + H2O` and balances it with the smallest whole coefficients
+    pub fn balance(&self, reaction: &str) -> Result<Reaction, ConversionError> {
+        let mut reaction = Reaction::parse(reaction, |symbol| self.element_aliases.get(symbol).copied())?;
+        reaction.balance()?;
+        Ok(reaction)
+    }
+
+    /// Finds the cycles of conversions whose factors multiply to something further than `tolerance` from 1,
+    /// which means the answer of a conversion depends on the path that is taken.<br>
+    /// Every conversion that is not in a spanning tree of the graph closes one cycle with the tree,
+    /// and any inconsistent cycle must contain at least one inconsistent cycle of those, so only those are checked
+    pub fn check_consistency(&self, tolerance: f64) -> Vec<Inconsistency> {
+        let graph = self.graph();
+        let parent = algorithm::spanning_forest(&graph);
+        let mut inconsistencies = Vec::new();
+        for (id, neighbors) in graph.iter().enumerate() {
+            for &Edge { node: next, .. } in neighbors {
+                if id > next || parent[next] == id || parent[id] == next {
+                    continue;
+                }
+                // along the tree from `id` to `next`, then back to `id` along the conversion that closes the cycle
+                let mut cycle = algorithm::tree_path(&parent, id, next);
+                cycle.push(id);
+                let factor: f64 = cycle.windows(2).map(|pair| self.factor(pair[0], pair[1])).product();
+                if (factor - 1f64).abs() > tolerance {
+                    let definitions = cycle.windows(2).map(|pair| self.definition(pair[0], pair[1])).collect();
+                    inconsistencies.push(Inconsistency { cycle, factor, definitions });
+                }
+            }
+        }
+        inconsistencies
+    }
+
+    /// Returns how many of `to` make up one `from`, when the two are linked directly
+    fn factor(&self, from: usize, to: usize) -> f64 {
+        self.unit_ids.get(&from).expect(ERR_ID_UNDEFINED)
+            .convert(to).expect("The units must be linked directly")
+            .factor()
+    }
+
+    /// Returns the `$` line that linked two units, or a description of the conversion if it was created implicitly,
+    /// like the one between kilometers and meters
+    fn definition(&self, unit_1: usize, unit_2: usize) -> String {
+        match self.definitions.get(&(unit_1.min(unit_2), unit_1.max(unit_2))) {
+            Some(definition) => definition.clone(),
+            None => format!("1 {} = {} {}",
+                self.unit_ids.get(&unit_1).expect(ERR_ID_UNDEFINED).get_name(),
+                self.factor(unit_1, unit_2),
+                self.unit_ids.get(&unit_2).expect(ERR_ID_UNDEFINED).get_name())
+        }
+    }
+
+    /// Converts a query whose units have already been resolved into ids
+    pub fn convert_query(&self, query: Query) -> Result<ConversionResult, ConversionError> {
+        if query.starting_numers.is_empty() && query.starting_denoms.is_empty() {
+            return Err(ConversionError::MissingUnits { side: "starting".to_string() });
+        }
+        if query.ending_numers.is_empty() && query.ending_denoms.is_empty() {
+            return Err(ConversionError::MissingUnits { side: "ending".to_string() });
+        }
+        let starting_dimension = self.dimension_of(&query.starting_numers, &query.starting_denoms)?;
+        let ending_dimension = self.dimension_of(&query.ending_numers, &query.ending_denoms)?;
+        if let (Some(starting_dimension), Some(ending_dimension)) = (starting_dimension, ending_dimension) {
+            if starting_dimension != ending_dimension {
+                return Err(ConversionError::IncompatibleDimensions { from: starting_dimension, to: ending_dimension });
+            }
+        }
+        let absolute = query.is_absolute();
+        let starting = (query.starting_numers.as_slice(), query.starting_denoms.as_slice());
+        let ending = (query.ending_numers.as_slice(), query.ending_denoms.as_slice());
+        let steps = match same_arity(starting, ending) {
+            // if the units cannot be matched directly they might still be after being broken down,
+            // but the units that were actually written are the ones worth reporting
+            true => match self.pair_units(starting, ending, absolute) {
+                Ok(steps) => steps,
+                Err(unmatched) => self.convert_through_basis(&query)
+                    .map_err(|_| self.unmatched_units(&query, &unmatched))?,
+            },
+            false => self.convert_through_basis(&query)?,
+        };
+        Ok(self.apply_steps(query, steps))
+    }
+
+    /// Applies every step to the value of a query, and works out how precise the answer is
+    fn apply_steps(&self, query: Query, steps: Vec<Step>) -> ConversionResult {
+        // units that are identical on both sides need no steps at all, so an empty list is still a complete conversion
+        let mut running_answer = query.value;
+        let mut exact_answer = query.exact_value.clone();
+        let mut uncertainty = query.uncertainty;
+        for step in &steps {
+            uncertainty = uncertainty.map(|uncertainty| step.propagate(running_answer, uncertainty));
+            step.apply(&mut running_answer);
+            exact_answer = exact_answer.and_then(|value| step.apply_exact(&value));
+        }
+        // rounding the exact answer once avoids the error that builds up from rounding after every step
+        let answer = exact_answer.as_ref().map_or(running_answer, rational::to_f64);
+        let significant_figures = match steps.iter().any(|step| step.offset() != 0f64) {
+            true => figures_after_offsets(&query, &steps, answer),
+            false => steps.iter()
+                .map(|step| significant_figures::from_relative_error(step.relative_error()))
+                .fold(query.significant_figures, significant_figures::min),
+        };
+        ConversionResult { query, steps, answer, exact_answer, significant_figures, uncertainty }
+    }
+
+    fn unmatched_units(&self, query: &Query, unmatched: &[usize]) -> ConversionError {
+        ConversionError::UnmatchedUnits {
+            unmatched: self.unit_names(unmatched),
+            from: self.units_to_string(&query.starting_numers, &query.starting_denoms),
+            to: self.units_to_string(&query.ending_numers, &query.ending_denoms),
+        }
+    }
+
+    /// Matches every starting unit with a different ending unit in the same component, and returns the steps along the
+    /// most precise path between each pair.<br>
+    /// Each starting unit prefers the same unit, then the most precise path, then the earliest ending unit.
+    /// The two sides must have the same arity. Returns the starting units that could not be matched if there are any
+    fn pair_units(&self, starting: (&[usize], &[usize]), ending: (&[usize], &[usize]), absolute: bool) -> Result<Vec<Step>, Vec<usize>> {
+        let mut steps = Vec::new();
+        let mut unmatched = Vec::new();
+        for (starts, ends, inverse) in [(starting.0, ending.0, false), (starting.1, ending.1, true)] {
+            // the cached bases rule out the ends in other components without searching
+            let searches: Vec<HashMap<usize, (usize, Cost)>> = starts.iter().map(|&start| {
+                let base = self.unit_ids.get(&start).expect(ERR_ID_UNDEFINED).get_base();
+                match ends.iter().any(|end| self.unit_ids.get(end).expect(ERR_ID_UNDEFINED).get_base() == base) {
+                    true => self.paths_from(start),
+                    false => HashMap::new(),
+                }
+            }).collect();
+            let candidates: Vec<Vec<usize>> = searches.iter().map(|tree| {
+                let mut candidates: Vec<(Cost, usize)> = ends.iter().enumerate()
+                    .filter_map(|(index, end)| tree.get(end).map(|(_, cost)| (*cost, index)))
+                    .collect();
+                candidates.sort();
+                candidates.into_iter().map(|(_, index)| index).collect()
+            }).collect();
+            for ((&start, tree), end) in starts.iter().zip(&searches).zip(algorithm::find_matching(&candidates, ends.len())) {
+                match end {
+                    Some(end) => steps.extend(self.path_steps(&algorithm::build_path(tree, ends[end]), inverse, absolute)),
+                    None => unmatched.push(start),
+                }
+            }
+        }
+        match unmatched.is_empty() {
+            true => Ok(steps),
+            false => Err(unmatched)
+        }
+    }
+
+    /// Breaks both sides of the query down into units that are not products of other units, then pairs those.<br>
+    /// This is what allows conversions like `1 acre : m^2`, where the two sides have a different number of units
+    fn convert_through_basis(&self, query: &Query) -> Result<Vec<Step>, ConversionError> {
+        let starting = self.decompose(&query.starting_numers, &query.starting_denoms)?;
+        let ending = self.decompose(&query.ending_numers, &query.ending_denoms)?;
+        if !same_arity((&starting.numers, &starting.denoms), (&ending.numers, &ending.denoms)) {
+            return Err(ConversionError::MismatchedArity {
+                from: self.units_to_string(&query.starting_numers, &query.starting_denoms),
+                to: self.units_to_string(&query.ending_numers, &query.ending_denoms),
+            });
+        }
+        match self.pair_units((&starting.numers, &starting.denoms), (&ending.numers, &ending.denoms), false) {
+            Ok(paired_steps) => {
+                let mut steps = starting.steps;
+                steps.extend(paired_steps);
+                // undo the decomposition of the ending units so that the answer is in the units that were asked for
+                steps.extend(ending.steps.iter().rev().map(Step::inverse));
+                Ok(steps)
+            },
+            Err(unmatched) => Err(self.unmatched_units(query, &unmatched))
+        }
+    }
+
+    /// Repeatedly replaces units with the product of units that they (or a unit connected to them) are defined as,
+    /// then cancels units that appear in both the numerator and the denominator
+    fn decompose(&self, numers: &[usize], denoms: &[usize]) -> Result<Decomposition, ConversionError> {
+        const MAX_EXPANSIONS: usize = 64;
+        let mut steps = Vec::new();
+        let mut numers = numers.to_vec();
+        let mut denoms = denoms.to_vec();
+        for _ in 0..MAX_EXPANSIONS {
+            let next = numers.iter().enumerate().map(|(index, id)| (false, index, *id))
+                .chain(denoms.iter().enumerate().map(|(index, id)| (true, index, *id)))
+                .find_map(|(in_denoms, index, id)| {
+                    self.nearest_composite(id).map(|path| (in_denoms, index, path))
+                });
+            let (in_denoms, index, path) = match next {
+                None => {
+                    cancel_common_units(&mut numers, &mut denoms);
+                    return Ok(Decomposition { steps, numers, denoms });
+                },
+                Some(thing) => thing
+            };
+            steps.extend(self.path_steps(&path, in_denoms, false));
+            let composite = *path.last().expect("A path must contain at least its start");
+            let composition = self.unit_ids.get(&composite).expect(ERR_ID_UNDEFINED).get_composition()
+                .expect("The nearest composite must have a composition");
+            let expansion = Step::expand(composition, composite);
+            let (replaced, opposite) = match in_denoms {
+                false => (&mut numers, &mut denoms),
+                true => (&mut denoms, &mut numers),
+            };
+            replaced.splice(index..index + 1, composition.numers.iter().copied());
+            opposite.extend(composition.denoms.iter().copied());
+            steps.push(if in_denoms { expansion.inverse() } else { expansion });
+        }
+        Err(ConversionError::DefinitionTooDeep)
+    }
+
+    /// Finds the most precise path from `id` to a unit that is defined as a product of other units.<br>
+    /// Ties are broken by the lower id. Returns `None` if no such unit is connected
+    fn nearest_composite(&self, id: usize) -> Option<Vec<usize>> {
+        let base = self.unit_ids.get(&id).expect(ERR_ID_UNDEFINED).get_base();
+        let is_composite = |member: &usize| self.unit_ids.get(member).expect(ERR_ID_UNDEFINED).get_composition().is_some();
+        // the cached component tells whether there is anything to search for
+        if !self.components.get(&base)?.iter().any(is_composite) {
+            return None;
+        }
+        let tree = self.paths_from(id);
+        let (_, composite) = tree.iter()
+            .filter(|(member, _)| is_composite(member))
+            .map(|(member, (_, cost))| (*cost, *member))
+            .min()?;
+        Some(algorithm::build_path(&tree, composite))
+    }
+
+    /// Formats the answer of a conversion so that it can be parsed again, like `50.0 Δfahrenheit`.<br>
+    /// The answer is rounded to the significant figures that the conversion is good for
+    pub fn answer_to_string(&self, result: &ConversionResult) -> String {
+        self.answer_with_units(result, result.rounded_answer())
+    }
+
+    /// Formats the answer of a conversion like [`answer_to_string`](Self::answer_to_string), but with every digit that was calculated
+    pub fn unrounded_answer_to_string(&self, result: &ConversionResult) -> String {
+        self.answer_with_units(result, result.unrounded_answer())
+    }
+
+    /// Formats the answer of a conversion as an exact fraction, like `63360/1 inches`.<br>
+    /// Returns `None` if the value or any step of the conversion was not exact
+    pub fn exact_answer_to_string(&self, result: &ConversionResult) -> Option<String> {
+        let exact_answer = result.exact_answer.as_ref()?;
+        Some(self.answer_with_units(result, rational::to_fraction_string(exact_answer)))
+    }
+
+    fn answer_with_units(&self, result: &ConversionResult, answer: String) -> String {
+        let query = &result.query;
+        let interval = if query.interval { "Δ" } else { "" };
+        format!("{answer} {interval}{}", self.units_to_string(&query.ending_numers, &query.ending_denoms))
+    }
+
+    /// Formats a value followed by the names of its units, like `3 m / s`
+    pub fn quantity_to_string(&self, value: f64, numers: &[usize], denoms: &[usize]) -> String {
+        format!("{} {}", value, self.units_to_string(numers, denoms))
+    }
+
+    /// Formats the names of units, like `m * kg / s`, or `/ s` if there are only units to divide by
+    pub fn units_to_string(&self, numers: &[usize], denoms: &[usize]) -> String {
+        let name = |id: &usize| self.unit_ids.get(id).expect(ERR_ID_UNDEFINED).get_name();
+        let mut s = numers.iter().map(name).collect::<Vec<_>>().join(" * ");
+        for denom in denoms {
+            s.push_str(format!(" / {}", name(denom)).as_str());
+        }
+        s.trim_start().to_string()
+    }
+
+    /// Formats the names of units with repeated units written once with their count, like `m^2 / s^2`
+    fn powers_to_string(&self, numers: &[usize], denoms: &[usize]) -> String {
+        let powers = |ids: &[usize]| {
+            let mut counts: Vec<(usize, usize)> = Vec::new();
+            for id in ids {
+                match counts.iter_mut().find(|(other, _)| other == id) {
+                    Some((_, count)) => *count += 1,
+                    None => counts.push((*id, 1)),
+                }
+            }
+            counts.into_iter().map(|(id, count)| {
+                let name = self.unit_ids.get(&id).expect(ERR_ID_UNDEFINED).get_name();
+                match count {
+                    1 => name.to_string(),
+                    _ => format!("{name}^{count}"),
+                }
+            }).collect::<Vec<_>>()
+        };
+        let mut s = powers(numers).join(" * ");
+        for denom in powers(denoms) {
+            s.push_str(format!(" / {denom}").as_str());
+        }
+        s.trim_start().to_string()
+    }
+
+    /// Creates (or reuses) the grams, moles and particles units of a chemical
+    fn insert_formula(&mut self, formula: &Formula) -> Result<Substance, ConversionError> {
+        let (molar_mass, mut name) = self.find_mm_and_name(&formula.elements)?;
+        name.push_str(&formula.charge_superscript());
+        if let Some(substance) = self.substances.get(&name) {
+            return Ok(*substance);
+        }
+        let moles = Unit::new(format!("moles [{name}]"), &mut self.units_generator);
+        let grams = Unit::new(format!("grams [{name}]"), &mut self.units_generator);
+        let particles = Unit::new(format!("particles [{name}]"), &mut self.units_generator);
+        let substance = Substance {
+            grams: grams.get_id(),
+            moles: moles.get_id(),
+            particles: particles.get_id(),
+        };
+        self.insert_unit(moles);
+        self.insert_unit(grams);
+        self.insert_unit(particles);
+        let grams_per_mole = Conversion::new(molar_mass.grams, 1f64)
+            .with_relative_error(molar_mass.uncertainty / molar_mass.grams)
+            .with_rational(molar_mass.exact_grams.map(RationalConversion::scale));
+        self.link(substance.moles, substance.grams, grams_per_mole)?;
+        self.link(substance.moles, substance.particles, Conversion::new(AVAGADROS_CONSTANT, 1f64).exact())?;
+        self.substances.insert(name, substance);
+        Ok(substance)
+    }
+
+    /// Returns the generic grams, moles and particles units, each paired with the one of `substance`
+    fn substance_units(&self, substance: Substance) -> Vec<(usize, usize)> {
+        [
+            (GRAMS_ALIAS, substance.grams),
+            (MOLES_ALIAS, substance.moles),
+            (PARTICLES_ALIAS, substance.particles),
+        ].into_iter()
+            .filter_map(|(alias, replacement)| self.unit_id(alias).map(|generic| (generic, replacement)))
+            .collect()
+    }
+
+    /// Replaces the generic grams, moles and particles units of a query with the ones of `substance`
+    fn substitute_substance(&self, query: &mut Query, substance: Substance) {
+        let replacements = self.substance_units(substance);
+        for ids in [&mut query.starting_numers, &mut query.starting_denoms, &mut query.ending_numers, &mut query.ending_denoms] {
+            for id in ids.iter_mut() {
+                if let Some((_, replacement)) = replacements.iter().find(|(generic, _)| generic == id) {
+                    *id = *replacement;
+                }
+            }
+        }
+    }
+
+    /// Returns the molar mass of a chemical and its name
+    fn find_mm_and_name(&self, elements: &[(usize, u32)]) -> Result<(MolarMass, String), ConversionError> {
+        let mut molar_mass = 0f64;
+        let mut exact_molar_mass = Some(Rational::zero());
+        let mut uncertainty = 0f64;
+        let mut name = String::new();
+        for (atomic_number, count) in elements {
+            match self.element_ids.get(atomic_number) {
+                None => return Err(ConversionError::UnknownAtomicNumber { atomic_number: *atomic_number }),
+                Some(element) => {
+                    molar_mass += element.molar_mass * f64::from(*count);
+                    exact_molar_mass = exact_molar_mass.zip(rational::from_f64(element.molar_mass))
+                        .map(|(sum, mass)| sum + mass * Rational::from_integer((*count).into()));
+                    // every atom of an element has the same error, so they add up directly, while different elements are independent
+                    uncertainty = uncertainty.hypot(element.uncertainty * f64::from(*count));
+                    name.push_str(element.symbol.as_str());
+                    // like in written formulas, a single atom has no subscript
+                    if *count != 1 {
+                        name.push_str(subscript_number(*count).as_str())
+                    }
+                }
+            };
+        }
+        Ok((MolarMass { grams: molar_mass, exact_grams: exact_molar_mass, uncertainty }, name))
+    }
+
+    /// Builds the adjacency list of every unit, weighting each edge by the relative error of its conversion
+    pub fn graph(&self) -> Vec<Vec<Edge>> {
+        (0..self.units_generator.peek()).map(|id| self.edges_of(id)).collect()
+    }
+
+    fn edges_of(&self, id: usize) -> Vec<Edge> {
+        let unit = self.unit_ids.get(&id).expect(ERR_ID_UNDEFINED);
+        unit.connected_ids().map(|neighbor| {
+            let conversion = unit.convert(*neighbor).expect("A unit must convert to the units it is connected to");
+            Edge { node: *neighbor, weight: conversion.relative_error() }
+        }).collect()
+    }
+
+    /// Reads `#` and `$` lines from a conversions file, ignoring every other line
+    pub fn load_units_from_file(&mut self, file_path: &Path) -> Result<(), ConversionError> {
+        let contents = read_file(file_path)?;
+        for line in contents.lines() {
+            match line.chars().next() {
+                Some('#') => { self.define_unit(line)?; },
+                Some('$') => { self.define_conversion(line)?; },
+                _ => continue
+            };
+        }
+        Ok(())
+    }
+
+    /// Reads one element per line, like `26 Fe, Iron = 55.845 ± 0.002 : period 4, group 8, block d`.<br>
+    /// Every line must start with its atomic number, and the elements must then run from 1 without gaps
+    pub fn load_elements_from_file(&mut self, file_path: &Path) -> Result<(), ConversionError> {
+        let contents = read_file(file_path)?;
+        for line in contents.lines() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+            if !line.starts_with(|c: char| c.is_ascii_digit()) {
+                return Err(ConversionError::malformed(format!("Element definition '{line}' must start with its atomic number")));
+            }
+            self.define_element(line)?;
+        }
+        let mut atomic_numbers: Vec<usize> = self.element_ids.keys().copied().collect();
+        atomic_numbers.sort_unstable();
+        if let Some(missing) = (1..).zip(atomic_numbers).find(|(expected, atomic_number)| expected != atomic_number) {
+            return Err(ConversionError::UnknownAtomicNumber { atomic_number: missing.0 });
+        }
+        Ok(())
+    }
+
+    /// Registers an element from a line like `2 He, Helium = 4.002602 ± 0.000002`, with its atomic number, symbol, name and any other aliases.<br>
+    /// Without an uncertainty, the mass is taken to be uncertain by one unit in its last digit.
+    /// A mass in brackets, like `[98]`, is the mass number of the longest-lived isotope of an element without a standard atomic weight.<br>
+    /// The mass can be followed by other properties, like `: period 1, group 18`.
+    /// Without an atomic number, the element takes the one after the largest so far. Returns its atomic number
+    pub fn define_element(&mut self, definition: &str) -> Result<usize, ConversionError> {
+        let (line, properties) = match definition.split_once(':') {
+            None => (terminate(definition), ""),
+            Some((line, properties)) => (terminate(line), properties.trim().trim_end_matches(';')),
+        };
+        let properties: Vec<(String, String)> = properties.split(',').map(str::trim)
+            .filter(|property| !property.is_empty())
+            .map(|property| match property.split_once(char::is_whitespace) {
+                Some((key, value)) => (key.to_string(), value.trim().to_string()),
+                None => (property.to_string(), String::new()),
+            })
+            .collect();
+        let mut line = line.as_str();
+        let digits = line.len() - line.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        let atomic_number = match digits {
+            0 => self.element_ids.keys().max().map_or(1, |largest| largest + 1),
+            _ => line[..digits].parse::<usize>()
+                .ok().filter(|atomic_number| *atomic_number > 0)
+                .ok_or_else(|| ConversionError::malformed(format!("'{}' is not a valid atomic number", &line[..digits])))?,
+        };
+        if let Some(existing) = self.element_ids.get(&atomic_number) {
+            return Err(ConversionError::DuplicateAtomicNumber { atomic_number, existing: existing.name.clone() });
+        }
+        line = line[digits..].trim_start();
+        let mut new_aliases = Vec::new();
+        loop {
+            if let Some((alias, length, terminator)) = extract_unit(line, &HashSet::from([',', '='])) {
+                line = line[length..].trim();
+                new_aliases.push(alias);
+                if terminator == '=' {
+                    break
+                }
+            } else {
+                return Err(ConversionError::malformed("Element definition must have an equals sign"));
+            }
+        }
+        if new_aliases.len() < 2 {
+            return Err(ConversionError::malformed("Element definition must have a symbol and a name before the equals sign"));
+        }
+        let (line, standard_weight) = match line.strip_prefix('[') {
+            Some(mass_number) => (mass_number.trim_start(), false),
+            None => (line, true),
+        };
+        let (molar_mass, size): (f64, usize) = match fast_float::parse_partial(line) {
+            Ok(thing) => thing,
+            Err(err) => return Err(ConversionError::malformed(format!("Element definition must have a valid number after the equals sign: fast_float says {err}")))
+        };
+        if !standard_weight && !line[size..].trim_start().starts_with(']') {
+            return Err(ConversionError::UnclosedBracket);
+        }
+        let uncertainty = match extract_uncertainty(&line[size..])? {
+            (Some(uncertainty), _) => uncertainty,
+            (None, _) => significant_figures::resolution(&line[..size]).unwrap_or(0f64),
+        };
+        let mut element = Element::new(new_aliases[0].clone(), new_aliases[1].clone(), atomic_number, molar_mass, uncertainty);
+        element.standard_weight = standard_weight;
+        element.properties = properties;
+        self.element_ids.insert(atomic_number, element);
+        for alias in new_aliases {
+            self.element_aliases.insert(alias, atomic_number);
+        }
+        Ok(atomic_number)
+    }
+}
+
+/// Parses how precise a conversion is, either `exact` or like `precision 1e-6`, into its relative error
+fn extract_precision(attribute: &str) -> Result<f64, ConversionError> {
+    if attribute == "exact" {
+        return Ok(0f64);
+    }
+    match attribute.strip_prefix("precision").map(|error| error.trim().parse::<f64>()) {
+        Some(Ok(error)) if error >= 0f64 => Ok(error),
+        _ => Err(ConversionError::malformed(format!(
+            "Invalid precision '{attribute}': expected `exact` or `precision` and a relative error, like `precision 1e-6`")))
+    }
+}
+
+/// Works out how many significant figures the answer of a conversion with offsets is good for, like `0 celsius : kelvin`.<br>
+/// Scaling keeps the relative precision of the value while adding an offset keeps its decimal places,
+/// so the place of the last digit is followed through the steps instead of the number of figures
+fn figures_after_offsets(query: &Query, steps: &[Step], answer: f64) -> Option<u32> {
+    let mut value = query.value;
+    let mut resolution = query.resolution;
+    for step in steps {
+        let scale = (step.top_value() / step.bottom_value()).abs();
+        value *= step.top_value() / step.bottom_value();
+        resolution = resolution.map(|resolution| resolution * scale);
+        // a factor that is not exact limits the answer to its own relative precision
+        if step.relative_error() > 0f64 {
+            resolution = Some(resolution.unwrap_or(0f64).max(value.abs() * step.relative_error()));
+        }
+        value += step.offset();
+    }
+    resolution.and_then(|resolution| significant_figures::from_resolution(answer, resolution))
+}
+
+fn same_arity(starting: (&[usize], &[usize]), ending: (&[usize], &[usize])) -> bool {
+    starting.0.len() == ending.0.len() && starting.1.len() == ending.1.len()
+}
+
+/// Divides the exact values of two sides of an equation, or returns `None` if either has none or the denominator is zero
+fn exact_ratio(numerator: &Query, denominator: &Query) -> Option<Rational> {
+    let denominator = denominator.exact_value.as_ref().filter(|value| !value.is_zero())?;
+    Some(numerator.exact_value.as_ref()? / denominator)
+}
+
+/// Returns the unit of a side of an equation if it is a lone unit, like `100 cm`
+fn single_unit(side: &Query) -> Option<usize> {
+    match (side.starting_numers.as_slice(), side.starting_denoms.is_empty()) {
+        ([unit], true) => Some(*unit),
+        _ => None
+    }
+}
+
+/// Removes every unit that appears in both the numerators and the denominators
+fn cancel_common_units(numers: &mut Vec<usize>, denoms: &mut Vec<usize>) {
+    let mut index = 0;
+    while index < numers.len() {
+        match denoms.iter().position(|id| *id == numers[index]) {
+            Some(position) => {
+                denoms.remove(position);
+                numers.remove(index);
+            },
+            None => index += 1
+        }
+    }
+}
+
+fn read_file(file_path: &Path) -> Result<String, ConversionError> {
+    fs::read_to_string(file_path).map_err(|err| ConversionError::FileRead {
+        path: file_path.display().to_string(),
+        reason: err.to_string(),
+    })
+}
@@ -0,0 +1,160 @@
+//! Serializable descriptions of conversions, for tools that read JSON instead of the fraction diagrams
+use crate::error::ConversionError;
+use serde::Serialize;
+
+/// The units of one side of a conversion, like the `m / s` in `3 m / s`
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct UnitsReport {
+    pub numerators: Vec<String>,
+    pub denominators: Vec<String>,
+}
+
+/// One [`Step`](crate::Step) with its units written out by name
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct StepReport {
+    pub top_value: f64,
+    pub top_units: Vec<String>,
+    pub bottom_value: f64,
+    pub bottom_units: Vec<String>,
+    pub offset: f64,
+}
+
+/// Everything about a successful conversion: what was asked, every step taken and the answer
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ConversionReport {
+    pub value: f64,
+    /// The uncertainty that the value was written with, like `0.2` for `12.3 ± 0.2 g`
+    pub value_uncertainty: Option<f64>,
+    pub interval: bool,
+    pub starting_units: UnitsReport,
+    pub ending_units: UnitsReport,
+    pub steps: Vec<StepReport>,
+    pub answer: f64,
+    /// The answer rounded to its significant figures and formatted with its units, like `4.27 feet`
+    pub answer_text: String,
+    /// The answer as an exact fraction, like `63360/1`, if every factor that led to it was exact
+    pub exact_answer: Option<String>,
+    /// How many significant figures the answer is good for, or `None` if every number that led to it was exact
+    pub significant_figures: Option<u32>,
+    /// The uncertainty of the answer, if the value was written with one
+    pub uncertainty: Option<f64>,
+}
+
+/// The conversions of several reactants into the same product, and which of them is the limiting reagent
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct LimitingReport {
+    pub results: Vec<ConversionReport>,
+    /// The index of the result whose reactant runs out first
+    pub limiting: usize,
+}
+
+impl LimitingReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reports only contain types that serialize to JSON")
+    }
+}
+
+/// One element's share of a molar mass
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ElementShare {
+    pub symbol: String,
+    pub name: String,
+    pub atomic_number: usize,
+    pub count: u32,
+    pub atomic_weight: f64,
+    /// False if the atomic weight is the mass number of the element's longest-lived isotope
+    pub standard_weight: bool,
+    /// How many grams per mole the element adds, which is its count times its atomic weight
+    pub mass: f64,
+    /// How much of the molar mass comes from the element, from 0 to 100
+    pub mass_percent: f64,
+}
+
+/// The molar mass of a chemical, broken down into the share of each element
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct MolarMassReport {
+    /// The formula as it was written, with subscripts, like `Ca(OH)₂`. The atoms of each element are counted in `elements`
+    pub formula: String,
+    pub charge: i32,
+    /// The elements in the order that each first appears in the formula
+    pub elements: Vec<ElementShare>,
+    /// The molar mass in grams per mole
+    pub molar_mass: f64,
+    /// How far off the molar mass may be, in grams per mole
+    pub uncertainty: f64,
+}
+
+impl MolarMassReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reports only contain types that serialize to JSON")
+    }
+}
+
+/// One element of a composition, and how many atoms of it the empirical formula has
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct ElementAmount {
+    pub symbol: String,
+    pub atomic_weight: f64,
+    /// The grams of the element in the sample, where a mass percent counts as grams of a 100 gram sample
+    pub grams: f64,
+    /// How many significant figures the amount was written with
+    pub significant_figures: Option<u32>,
+    pub moles: f64,
+    /// The moles divided by the moles of the element that there is the least of
+    pub ratio: f64,
+    /// The ratio times the multiplier, rounded to a whole number
+    pub count: u32,
+}
+
+/// The empirical formula of a compound found from the amount of each element in it, and its molecular formula if its molar mass is known
+#[derive(Serialize, Clone, Debug, PartialEq)]
+pub struct EmpiricalReport {
+    pub elements: Vec<ElementAmount>,
+    /// What every ratio was multiplied by to get whole numbers, like 2 for ratios of 1 and 1.5
+    pub multiplier: u32,
+    /// The empirical formula written with subscripts, like `CH₂O`
+    pub empirical_formula: String,
+    pub empirical_molar_mass: f64,
+    /// The molar mass of the compound that was given
+    pub molar_mass: Option<f64>,
+    /// How many times the empirical formula fits into the molar mass
+    pub molecular_multiple: Option<u32>,
+    /// The molecular formula written with subscripts, like `C₆H₁₂O₆`
+    pub molecular_formula: Option<String>,
+}
+
+impl EmpiricalReport {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reports only contain types that serialize to JSON")
+    }
+}
+
+/// The outcome of a conversion, serialized with a `status` of either `success` or `error`.<br>
+/// An error carries its `kind` along with the message that the REPL would print
+#[derive(Serialize, Clone, Debug, PartialEq)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum Report {
+    Success(ConversionReport),
+    Error { error: ConversionError, message: String },
+}
+
+impl Report {
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Reports only contain types that serialize to JSON")
+    }
+}
+
+impl From<Result<ConversionReport, ConversionError>> for Report {
+    fn from(result: Result<ConversionReport, ConversionError>) -> Self {
+        match result {
+            Ok(report) => Report::Success(report),
+            Err(err) => Report::from(err),
+        }
+    }
+}
+
+impl From<ConversionError> for Report {
+    fn from(error: ConversionError) -> Self {
+        Report::Error { message: error.to_string(), error }
+    }
+}
@@ -0,0 +1,135 @@
+/// Answers at least this many times larger than 1, or this many times smaller, are written in scientific notation
+const SCIENTIFIC_MAGNITUDE: i32 = 6;
+
+/// Counts the significant figures of a number as it was written, like 3 for `1.30`, `0.00130` or `1.30e5`.<br>
+/// Zeros at the end of a whole number only count if it ends with a decimal point, so `1300` has 2 but `1300.` has 4.
+/// A number with no other digits than zeros has as many as it has decimals, and at least 1, since a bare `0` still tells
+/// the place that it was measured to. Returns `None` if the text has no digits
+pub fn count(text: &str) -> Option<u32> {
+    let mantissa = text.trim().split(['e', 'E']).next()?;
+    let mantissa = mantissa.trim_start_matches(['-', '+']);
+    let (whole, fraction) = mantissa.split_once('.').unwrap_or((mantissa, ""));
+    if !(whole.bytes().chain(fraction.bytes()).all(|b| b.is_ascii_digit())) || whole.len() + fraction.len() == 0 {
+        return None;
+    }
+    let digits = format!("{whole}{fraction}");
+    let digits = digits.trim_start_matches('0');
+    let count = match mantissa.contains('.') {
+        // every zero of `0.00` is significant, since there is no other digit to tell how precise it is
+        true if digits.is_empty() => fraction.len(),
+        true => digits.len(),
+        false => digits.trim_end_matches('0').len(),
+    };
+    Some(u32::try_from(count.max(1)).unwrap_or(u32::MAX))
+}
+
+/// Returns how many significant figures a factor with this relative error is good for, like 6 for `1e-6`.<br>
+/// Returns `None` for exact factors, which do not limit the answer
+pub fn from_relative_error(relative_error: f64) -> Option<u32> {
+    if relative_error <= 0f64 {
+        return None;
+    }
+    Some((-relative_error.log10()).floor().max(1f64) as u32)
+}
+
+/// Returns how many significant figures `value` has when its last digit is at the place of `resolution`,
+/// like 3 for `273.15` with a resolution of 1 or 1.8. This is how sums are rounded: to the decimal places rather than the figures.<br>
+/// Returns `None` if the resolution is not positive, and at least 1 otherwise
+pub fn from_resolution(value: f64, resolution: f64) -> Option<u32> {
+    if resolution <= 0f64 || !resolution.is_finite() {
+        return None;
+    }
+    if value == 0f64 {
+        return Some(1);
+    }
+    let magnitude = value.abs().log10().floor() as i32;
+    // a scaled resolution, like 1.8 for a degree Celsius in Fahrenheit, is closest to the place of 1
+    let place = resolution.log10().round() as i32;
+    Some((magnitude - place + 1).max(1) as u32)
+}
+
+/// Returns the size of one unit in the last significant figure of a value, like `0.01` for `4.27` with 3 figures.
+/// The inverse of [`from_resolution`]
+pub fn resolution_of(value: f64, figures: u32) -> f64 {
+    let magnitude = if value == 0f64 { 0 } else { value.abs().log10().floor() as i32 };
+    10f64.powi(magnitude + 1 - figures as i32)
+}
+
+/// Returns the fewer of two numbers of significant figures, where `None` stands for an exact number
+pub fn min(first: Option<u32>, second: Option<u32>) -> Option<u32> {
+    match (first, second) {
+        (Some(first), Some(second)) => Some(first.min(second)),
+        (first, second) => first.or(second),
+    }
+}
+
+/// Writes `value` rounded to `figures` significant figures, in a way that shows how many there are and can be parsed again,
+/// like `4.27`, `1300.` or `6.02e23`
+pub fn format(value: f64, figures: u32) -> String {
+    if value == 0f64 || !value.is_finite() {
+        return value.to_string();
+    }
+    let figures = figures.clamp(1, 17);
+    let magnitude = |value: f64| value.abs().log10().floor() as i32;
+    let decimals = figures as i32 - 1 - magnitude(value);
+    let rounded = round_to(value, decimals);
+    // rounding can carry into a new digit, like 9.99 to 10.0, which leaves one decimal fewer
+    let decimals = figures as i32 - 1 - magnitude(rounded);
+    let scientific = format!("{:.*e}", figures as usize - 1, rounded);
+    if magnitude(rounded).abs() >= SCIENTIFIC_MAGNITUDE {
+        return scientific;
+    }
+    if decimals > 0 {
+        return format!("{:.*}", decimals as usize, rounded);
+    }
+    let whole = format!("{rounded:.0}");
+    [whole.clone(), format!("{whole}.")].into_iter()
+        .find(|candidate| count(candidate) == Some(figures))
+        .unwrap_or(scientific)
+}
+
+/// Writes a value and its uncertainty, like `0.683 ± 0.011` or `12.3 ± 0.2`, with the value rounded to the last digit of the uncertainty.<br>
+/// The uncertainty keeps two significant figures if it starts with a 1, and one otherwise
+pub fn format_with_uncertainty(value: f64, uncertainty: f64) -> String {
+    if uncertainty <= 0f64 || !uncertainty.is_finite() || !value.is_finite() {
+        return format!("{value} ± {uncertainty}");
+    }
+    let magnitude = |value: f64| value.abs().log10().floor() as i32;
+    let figures = match (uncertainty / 10f64.powi(magnitude(uncertainty))).floor() as u32 {
+        1 => 2,
+        _ => 1,
+    };
+    let uncertainty = round_to(uncertainty, figures - 1 - magnitude(uncertainty));
+    // rounding can carry into a new digit, like 0.096 to 0.1, which leaves one decimal fewer
+    let decimals = figures - 1 - magnitude(uncertainty);
+    let value = round_to(value, decimals);
+    let largest = if value == 0f64 { magnitude(uncertainty) } else { magnitude(value).max(magnitude(uncertainty)) };
+    if largest.abs() >= SCIENTIFIC_MAGNITUDE {
+        let value_decimals = (largest - magnitude(uncertainty) + figures - 1).max(0) as usize;
+        let uncertainty_decimals = (figures - 1) as usize;
+        return format!("{value:.value_decimals$e} ± {uncertainty:.uncertainty_decimals$e}");
+    }
+    let decimals = decimals.max(0) as usize;
+    format!("{value:.decimals$} ± {uncertainty:.decimals$}")
+}
+
+/// Returns the size of one unit in the last digit of a number as it was written, like `0.001` for `1.008`
+/// or `100` for `1.3e3`. Returns `None` if the text is not a number
+pub fn resolution(text: &str) -> Option<f64> {
+    count(text)?;
+    let (mantissa, exponent) = match text.trim().split_once(['e', 'E']) {
+        None => (text.trim(), 0),
+        Some((mantissa, exponent)) => (mantissa, exponent.strip_prefix('+').unwrap_or(exponent).parse::<i32>().ok()?),
+    };
+    let decimals = mantissa.split_once('.').map_or(0, |(_, fraction)| fraction.len() as i32);
+    Some(10f64.powi(exponent - decimals))
+}
+
+/// Rounds `value` to `decimals` places after the decimal point, or to tens, hundreds and so on if it is negative
+fn round_to(value: f64, decimals: i32) -> f64 {
+    let scale = 10f64.powi(decimals.abs());
+    match decimals >= 0 {
+        true => (value * scale).round() / scale,
+        false => (value / scale).round() * scale,
+    }
+}
@@ -1,29 +1,132 @@
-use std::collections::HashMap;
-use std::collections::hash_map::Keys;
+use crate::prefixes::PrefixSet;
+use crate::rational::{self, Rational, RationalConversion};
+use crate::significant_figures;
+use std::collections::{BTreeMap, HashMap};
+use std::collections::btree_map::Keys;
+use serde::Serialize;
+use std::fmt;
 use std::hash::{Hash, Hasher};
-use std::ops::{DivAssign, MulAssign};
+use std::ops::{AddAssign, DivAssign, MulAssign};
+use num_traits::{One, Signed, Zero};
 
+/// Maps a value `x` onto `x * numerator / denominator + offset`
 #[derive(Clone)]
 pub struct Conversion {
     numerator: f64,
     denominator: f64,
+    offset: f64,
+    /// How far off the factor may be, relative to its size. Zero for factors that are exact by definition
+    relative_error: f64,
+    /// The same map as exact fractions, or `None` if it was built from a number that is not a plain decimal
+    rational: Option<RationalConversion>,
 }
 
 pub struct Unit {
     name: String,
     id: usize,
-    edges: HashMap<usize, Conversion>,
+    /// Kept in order of id so that searches through the graph always visit neighbors in the same order
+    edges: BTreeMap<usize, Conversion>,
+    dimension: Option<Dimension>,
+    composition: Option<Composition>,
+    prefixes: Option<PrefixSet>,
+    /// The unit that stands for every unit connected to this one, so that new conversions can be checked against it
+    base: usize,
+    /// Converts this unit into `base`, along the most precise path that was known when it was cached
+    to_base: Conversion,
 }
 
+/// The exponents of the base quantities that a unit is made of, like `length·time⁻¹` for a speed
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash, Serialize)]
+#[serde(into = "String")]
+pub struct Dimension {
+    exponents: [i8; Dimension::BASE_QUANTITIES.len()],
+}
+
+/// One fraction in a chain of dimensional analysis.<br>
+/// It multiplies by `top_value` and the `top_ids` units and divides by `bottom_value` and the `bottom_ids` units
+#[derive(Clone)]
 pub struct Step {
     top_value: f64,
-    top_id: usize,
+    top_ids: Vec<usize>,
     bottom_value: f64,
-    bottom_id: usize,
+    bottom_ids: Vec<usize>,
+    offset: f64,
+    rational: Option<RationalConversion>,
+    relative_error: f64,
+}
+
+/// The definition of a unit as a product of other units, like `1 acre = 43560 ft^2`
+#[derive(Clone)]
+pub struct Composition {
+    pub factor: f64,
+    /// The factor as an exact fraction, if it was written as plain decimals
+    pub exact_factor: Option<Rational>,
+    /// How far off the factor may be, relative to its size, like a [`Conversion`]
+    pub relative_error: f64,
+    pub numers: Vec<usize>,
+    pub denoms: Vec<usize>,
 }
 
 pub struct IDGenerator {
-    id: usize
+    id: usize,
+    initial_id: usize,
+}
+
+/// A parsed conversion request: a value, the units it starts in, and the units it should end in
+#[derive(Clone, Default)]
+pub struct Query {
+    pub value: f64,
+    /// The value as the exact fraction that it was written as, or `None` if it was not written as plain decimals
+    pub exact_value: Option<Rational>,
+    /// How many significant figures the value was written with, or `None` if it was not written or is exact
+    pub significant_figures: Option<u32>,
+    /// The size of one unit in the last digit of the value, like `0.1` for `20.5`, if it was written as a single number.
+    /// Offsets are added to this place rather than to the significant figures
+    pub resolution: Option<f64>,
+    /// The uncertainty that the value was written with, like `0.2` for `12.3 ± 0.2 g`
+    pub uncertainty: Option<f64>,
+    pub starting_numers: Vec<usize>,
+    pub starting_denoms: Vec<usize>,
+    pub ending_numers: Vec<usize>,
+    pub ending_denoms: Vec<usize>,
+    /// True when the value is a difference, written like `delta celsius` or `Δ°C`, so offsets never apply to it
+    pub interval: bool,
+}
+
+/// The outcome of a successful conversion.<br>
+/// `steps` holds every conversion factor that was applied to `query.value` to reach `answer`
+pub struct ConversionResult {
+    pub query: Query,
+    pub steps: Vec<Step>,
+    /// The nearest float to `exact_answer` if there is one, so that it is only rounded once
+    pub answer: f64,
+    /// The answer as an exact fraction, if the value and every step were exact fractions
+    pub exact_answer: Option<Rational>,
+    /// How many significant figures the answer is good for: the fewest of the value and of every step that is not exact.
+    /// `None` if they were all exact
+    pub significant_figures: Option<u32>,
+    /// The uncertainty of the value carried through every step, if the value had one
+    pub uncertainty: Option<f64>,
+}
+
+impl ConversionResult {
+    /// Writes the answer rounded to its significant figures, like `4.27`, or in full if they are not limited.<br>
+    /// If it has an uncertainty, the uncertainty decides where it is rounded instead, like `0.683 ± 0.011`
+    pub fn rounded_answer(&self) -> String {
+        match (self.uncertainty, self.significant_figures) {
+            (Some(uncertainty), _) => significant_figures::format_with_uncertainty(self.answer, uncertainty),
+            (None, Some(figures)) => significant_figures::format(self.answer, figures),
+            (None, None) => self.answer.to_string(),
+        }
+    }
+
+    /// Writes the answer, and its uncertainty if it has one, with every digit that was calculated
+    pub fn unrounded_answer(&self) -> String {
+        match self.uncertainty {
+            Some(uncertainty) => format!("{} ± {uncertainty}", self.answer),
+            None => self.answer.to_string(),
+        }
+    }
 }
 
 impl Step {
@@ -34,28 +137,163 @@ impl Step {
         Step{
             top_value: conversion.numerator,
             bottom_value: conversion.denominator,
-            top_id: to_id,
-            bottom_id: from_id
+            top_ids: vec![to_id],
+            bottom_ids: vec![from_id],
+            offset: conversion.offset,
+            rational: conversion.rational.clone(),
+            relative_error: conversion.relative_error,
         }
     }
 
+    /// Creates the step that replaces `unit_id` with the units of its `composition`
+    pub fn expand(composition: &Composition, unit_id: usize) -> Self {
+        let mut bottom_ids = vec![unit_id];
+        bottom_ids.extend(&composition.denoms);
+        Step {
+            top_value: composition.factor,
+            bottom_value: 1f64,
+            top_ids: composition.numers.clone(),
+            bottom_ids,
+            offset: 0f64,
+            rational: composition.exact_factor.clone().map(RationalConversion::scale),
+            relative_error: composition.relative_error,
+        }
+    }
+
+    /// Returns the step that undoes this one
+    pub fn inverse(&self) -> Step {
+        Step {
+            top_value: self.bottom_value,
+            bottom_value: self.top_value,
+            top_ids: self.bottom_ids.clone(),
+            bottom_ids: self.top_ids.clone(),
+            // adding 0 turns the -0 that negating an offset of 0 gives back into 0
+            offset: -self.offset * self.bottom_value / self.top_value + 0f64,
+            rational: self.rational.as_ref().and_then(RationalConversion::inverse),
+            relative_error: self.relative_error,
+        }
+    }
+
+    pub fn apply(&self, value: &mut f64) {
+        value.mul_assign(self.top_value);
+        value.div_assign(self.bottom_value);
+        value.add_assign(self.offset);
+    }
+
+    /// Returns the uncertainty of `value` after this step, given the uncertainty it had before.<br>
+    /// The relative error of the step's factor adds to it as an independent uncertainty
+    pub fn propagate(&self, value: f64, uncertainty: f64) -> f64 {
+        let scale = (self.top_value / self.bottom_value).abs();
+        (uncertainty * scale).hypot(value * scale * self.relative_error)
+    }
+
+    /// Applies the step to an exact value. Returns `None` if the step itself is not exact
+    pub fn apply_exact(&self, value: &Rational) -> Option<Rational> {
+        self.rational.as_ref().map(|rational| rational.apply(value))
+    }
+
     pub fn get_top(&self, unit_ids: &HashMap<usize, Unit>) -> String {
-        format!("{} {}", self.top_value, unit_ids.get(&self.top_id).expect(Self::ERROR).get_name())
+        let top = match self.exact_values() {
+            Some((top, _)) => rational::to_exact_string(&top),
+            None => self.top_value.to_string(),
+        };
+        format!("{} {}", top, Self::names(&self.top_ids, unit_ids))
+    }
+
+    /// Returns the top and bottom values as exact fractions, if the step is exact.<br>
+    /// The values are kept as they were written when they give the exact factor, otherwise the side that is not 1
+    /// follows from it, so that a rounded float like `17.77777777777778` is never shown
+    fn exact_values(&self) -> Option<(Rational, Rational)> {
+        let scale = &self.rational.as_ref()?.scale;
+        let top = rational::from_f64(self.top_value)?;
+        let bottom = rational::from_f64(self.bottom_value).filter(|bottom| !bottom.is_zero())?;
+        if &top / &bottom == *scale {
+            return Some((top, bottom));
+        }
+        match top.is_one() && !scale.is_zero() {
+            true => Some((top, scale.recip())),
+            false => Some((scale * &bottom, bottom)),
+        }
+    }
+
+    /// Like [`get_top`](Self::get_top), but with the uncertainty of the factor, like `18.0150 ± 0.0022 grams`
+    pub fn get_uncertain_top(&self, unit_ids: &HashMap<usize, Unit>) -> String {
+        format!("{} {}", self.uncertain_value(self.top_value), Self::names(&self.top_ids, unit_ids))
+    }
+
+    /// Like [`get_bottom`](Self::get_bottom), but with the uncertainty of the factor
+    pub fn get_uncertain_bottom(&self, unit_ids: &HashMap<usize, Unit>) -> String {
+        format!("{} {}", self.uncertain_value(self.bottom_value), Self::names(&self.bottom_ids, unit_ids))
+    }
+
+    fn uncertain_value(&self, value: f64) -> String {
+        significant_figures::format_with_uncertainty(value, value.abs() * self.relative_error)
     }
 
     pub fn get_bottom(&self, unit_ids: &HashMap<usize, Unit>) -> String {
-        format!("{} {}", self.bottom_value, unit_ids.get(&self.bottom_id).expect(Self::ERROR).get_name())
+        let bottom = match self.exact_values() {
+            Some((_, bottom)) => rational::to_exact_string(&bottom),
+            None => self.bottom_value.to_string(),
+        };
+        format!("{} {}", bottom, Self::names(&self.bottom_ids, unit_ids))
+    }
+
+    fn names(ids: &[usize], unit_ids: &HashMap<usize, Unit>) -> String {
+        ids.iter()
+            .map(|id| unit_ids.get(id).expect(Self::ERROR).get_name())
+            .collect::<Vec<_>>()
+            .join(" × ")
+    }
+
+    /// Returns the offset that is added after multiplying by the fraction, like `+ 273.15 kelvin`.<br>
+    /// Returns `None` if the step has no offset
+    pub fn get_offset(&self, unit_ids: &HashMap<usize, Unit>) -> Option<String> {
+        if self.offset == 0f64 {
+            return None;
+        }
+        let sign = if self.offset < 0f64 { '-' } else { '+' };
+        let offset = match &self.rational {
+            Some(rational) => rational::to_exact_string(&rational.offset.abs()),
+            None => self.offset.abs().to_string(),
+        };
+        Some(format!("{} {} {}", sign, offset, Self::names(&self.top_ids, unit_ids)))
+    }
+
+    pub fn top_value(&self) -> f64 {
+        self.top_value
+    }
+
+    pub fn top_ids(&self) -> &[usize] {
+        &self.top_ids
+    }
+
+    pub fn bottom_value(&self) -> f64 {
+        self.bottom_value
+    }
+
+    pub fn bottom_ids(&self) -> &[usize] {
+        &self.bottom_ids
+    }
+
+    pub fn offset(&self) -> f64 {
+        self.offset
+    }
+
+    pub fn relative_error(&self) -> f64 {
+        self.relative_error
     }
 }
 
 impl IDGenerator {
     pub fn new(initial_id: usize) -> Self {
         IDGenerator {
-            id: initial_id
+            id: initial_id,
+            initial_id,
         }
     }
 
     /// Returns a new, unique id that is 1 greater than the previous id
+    #[allow(clippy::should_implement_trait)]
     pub fn next(&mut self) -> usize {
         self.id += 1;
         self.id - 1
@@ -66,46 +304,280 @@ impl IDGenerator {
         self.id
     }
 
+    /// Resets the generator back to the id it was created with
     pub fn clear(&mut self) {
-        self.id = 0;
+        self.id = self.initial_id;
+    }
+}
+
+impl Query {
+    /// Returns true if both sides are a single unit and the value is not an interval, like `10 celsius : fahrenheit`.<br>
+    /// Only these conversions apply offsets; in a compound like `J / celsius` every unit is an interval
+    pub fn is_absolute(&self) -> bool {
+        !self.interval
+            && self.starting_numers.len() == 1 && self.starting_denoms.is_empty()
+            && self.ending_numers.len() == 1 && self.ending_denoms.is_empty()
     }
 }
 
 impl Conversion {
+    /// The relative error of conversions that were not marked with how precise they are
+    pub const DEFAULT_RELATIVE_ERROR: f64 = 1e-6;
+
+    /// Creates the conversion `numerator / denominator`.
+    /// Its exact form is the fraction of the shortest decimals that the two floats are written as
     pub fn new(numerator: f64, denominator: f64) -> Self {
         Conversion {
             numerator,
             denominator,
+            offset: 0f64,
+            relative_error: Self::DEFAULT_RELATIVE_ERROR,
+            rational: RationalConversion::ratio(numerator, denominator),
+        }
+    }
+
+    /// Marks this conversion as exact by definition, like `1 inch = 2.54 cm`
+    pub fn exact(self) -> Self {
+        self.with_relative_error(0f64)
+    }
+
+    pub fn with_relative_error(mut self, relative_error: f64) -> Self {
+        self.relative_error = relative_error;
+        self
+    }
+
+    pub fn relative_error(&self) -> f64 {
+        self.relative_error
+    }
+
+    /// Replaces the exact form of this conversion, like with the fraction of the decimals that it was defined with
+    pub fn with_rational(mut self, rational: Option<RationalConversion>) -> Self {
+        self.rational = rational;
+        self
+    }
+
+    pub fn rational(&self) -> Option<&RationalConversion> {
+        self.rational.as_ref()
+    }
+
+    /// Creates the conversion that maps `first.0` onto `first.1` and `second.0` onto `second.1`.<br>
+    /// The fraction is scaled so that its smaller side is 1, like `1 celsius / 1.8 fahrenheit`
+    pub fn through_points(first: (f64, f64), second: (f64, f64)) -> Self {
+        let numerator = second.1 - first.1;
+        let denominator = second.0 - first.0;
+        let smaller = numerator.abs().min(denominator.abs());
+        let exact = |point: (f64, f64)| rational::from_f64(point.0).zip(rational::from_f64(point.1));
+        let rational = exact(first).zip(exact(second))
+            .and_then(|(first, second)| RationalConversion::through_points((&first.0, &first.1), (&second.0, &second.1)));
+        Conversion {
+            numerator: numerator / smaller,
+            denominator: denominator / smaller,
+            offset: first.1 - first.0 * numerator / denominator,
+            relative_error: Self::DEFAULT_RELATIVE_ERROR,
+            rational,
         }
     }
 
     pub fn apply(&self, value: &mut f64) {
         value.mul_assign(self.numerator);
         value.div_assign(self.denominator);
+        value.add_assign(self.offset);
     }
 
     pub fn inverse(&self) -> Conversion {
         Conversion {
             numerator: self.denominator,
-            denominator: self.numerator
+            denominator: self.numerator,
+            // adding 0 turns the -0 that negating an offset of 0 gives back into 0
+            offset: -self.offset * self.denominator / self.numerator + 0f64,
+            relative_error: self.relative_error,
+            rational: self.rational.as_ref().and_then(RationalConversion::inverse),
+        }
+    }
+
+    /// Returns how many of the second unit make up one of the first, ignoring the offset
+    pub fn factor(&self) -> f64 {
+        self.numerator / self.denominator
+    }
+
+    /// Returns the conversion that applies this one and then `next`. Their relative errors add up
+    pub fn then(&self, next: &Conversion) -> Conversion {
+        Conversion {
+            numerator: self.numerator * next.numerator,
+            denominator: self.denominator * next.denominator,
+            offset: self.offset * next.numerator / next.denominator + next.offset,
+            relative_error: self.relative_error + next.relative_error,
+            rational: self.rational.as_ref().zip(next.rational.as_ref()).map(|(first, next)| first.then(next)),
+        }
+    }
+
+    /// Scales the fraction so that its smaller side is 1, like `1 mile / 1.609344 km`
+    pub fn normalized(&self) -> Conversion {
+        let smaller = self.numerator.abs().min(self.denominator.abs());
+        Conversion {
+            numerator: self.numerator / smaller,
+            denominator: self.denominator / smaller,
+            ..self.clone()
+        }
+    }
+
+    /// Returns this conversion without its offset, which is how it applies to anything but a plain absolute value
+    pub fn scale_only(&self) -> Conversion {
+        Conversion {
+            offset: 0f64,
+            rational: self.rational.as_ref().map(RationalConversion::scale_only),
+            ..self.clone()
+        }
+    }
+}
+
+impl Dimension {
+    pub const BASE_QUANTITIES: [&'static str; 7] = ["length", "mass", "time", "temperature", "amount", "current", "luminosity"];
+
+    /// Returns the dimension of a single base quantity, like `length`
+    pub fn base(quantity: &str) -> Option<Self> {
+        let index = Self::BASE_QUANTITIES.iter().position(|name| *name == quantity)?;
+        let mut dimension = Dimension::default();
+        dimension.exponents[index] = 1;
+        Some(dimension)
+    }
+
+    /// Raises every exponent to `exponent`. Returns `None` if one of them overflows
+    pub fn checked_powi(self, exponent: i8) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for e in exponents.iter_mut() {
+            *e = e.checked_mul(exponent)?;
+        }
+        Some(Dimension { exponents })
+    }
+
+    /// Multiplies two dimensions by adding their exponents. Returns `None` if one of them overflows
+    pub fn checked_mul(self, rhs: Self) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for (e, other) in exponents.iter_mut().zip(rhs.exponents) {
+            *e = e.checked_add(other)?;
+        }
+        Some(Dimension { exponents })
+    }
+
+    /// Divides two dimensions by subtracting their exponents. Returns `None` if one of them overflows
+    pub fn checked_div(self, rhs: Self) -> Option<Self> {
+        let mut exponents = self.exponents;
+        for (e, other) in exponents.iter_mut().zip(rhs.exponents) {
+            *e = e.checked_sub(other)?;
+        }
+        Some(Dimension { exponents })
+    }
+
+    pub fn is_dimensionless(&self) -> bool {
+        self.exponents.iter().all(|e| *e == 0)
+    }
+}
+
+impl From<Dimension> for String {
+    fn from(dimension: Dimension) -> Self {
+        dimension.to_string()
+    }
+}
+
+impl fmt::Display for Dimension {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fn superscript(num: i8) -> String {
+            num.to_string().chars().map(|c| match c {
+                '-' => '⁻',
+                '0' => '⁰',
+                '1' => '¹',
+                '2' => '²',
+                '3' => '³',
+                '4' => '⁴',
+                '5' => '⁵',
+                '6' => '⁶',
+                '7' => '⁷',
+                '8' => '⁸',
+                '9' => '⁹',
+                _ => panic!("All chars must be ascii digits or '-' when creating superscript")
+            }).collect()
+        }
+        if self.is_dimensionless() {
+            return f.write_str("dimensionless");
+        }
+        let mut parts = Vec::new();
+        for (name, exponent) in Self::BASE_QUANTITIES.iter().zip(self.exponents) {
+            match exponent {
+                0 => continue,
+                1 => parts.push(name.to_string()),
+                _ => parts.push(format!("{name}{}", superscript(exponent)))
+            }
         }
+        f.write_str(parts.join("·").as_str())
     }
 }
 
 impl Unit {
     pub fn new(name: String, gen: &mut IDGenerator) -> Self {
+        let id = gen.next();
         Unit {
             name,
-            id: gen.next(),
-            edges: HashMap::new()
+            id,
+            edges: BTreeMap::new(),
+            dimension: None,
+            composition: None,
+            prefixes: None,
+            base: id,
+            to_base: Conversion::new(1f64, 1f64).exact(),
         }
     }
 
+    /// Returns the unit of this unit's connected component that the cached conversions of all its units lead to.<br>
+    /// Two units can be converted if they have the same base, but conversions still search for the most precise path between them
+    pub fn get_base(&self) -> usize {
+        self.base
+    }
+
+    /// Returns the cached conversion from this unit into its base
+    pub fn to_base(&self) -> &Conversion {
+        &self.to_base
+    }
+
+    /// Caches the conversion into `base` without its exact fraction, which is never used and whose digits grow with every unit along the way
+    pub fn set_base(&mut self, base: usize, to_base: Conversion) {
+        self.base = base;
+        self.to_base = to_base.with_rational(None);
+    }
+
+    /// Returns which prefixes, like kilo-, can be put in front of this unit
+    pub fn get_prefixes(&self) -> Option<PrefixSet> {
+        self.prefixes
+    }
+
+    pub fn set_prefixes(&mut self, prefixes: PrefixSet) {
+        self.prefixes = Some(prefixes);
+    }
+
+    /// Returns the product of other units that this unit was defined as, if there is one
+    pub fn get_composition(&self) -> Option<&Composition> {
+        self.composition.as_ref()
+    }
+
+    pub fn set_composition(&mut self, composition: Composition) {
+        self.composition = Some(composition);
+    }
+
+    /// Returns the dimension of this unit, or `None` if nothing connected to it has declared one
+    pub fn get_dimension(&self) -> Option<Dimension> {
+        self.dimension
+    }
+
+    pub fn set_dimension(&mut self, dimension: Dimension) {
+        self.dimension = Some(dimension);
+    }
+
     pub fn push_edge(&mut self, other: &Self, conversion: Conversion) {
         self.edges.insert(other.get_id(), conversion);
     }
 
-    pub fn connected_ids(&self) -> Keys<usize, Conversion> {
+    /// Returns the ids of the units that this unit converts to directly, from lowest to highest
+    pub fn connected_ids(&self) -> Keys<'_, usize, Conversion> {
         self.edges.keys()
     }
 
@@ -136,22 +608,49 @@ impl PartialEq for Unit {
     fn eq(&self, other: &Self) -> bool {
         self.name.eq(&other.name)
     }
-
-    fn ne(&self, other: &Self) -> bool {
-        self.name.ne(&other.name)
-    }
 }
 
 impl Eq for Unit { }
 
+/// The conversions of several reactants into the same product, found by [`Registry::limiting_reagent`](crate::Registry::limiting_reagent)
+pub struct LimitingReagent {
+    /// The conversion of each reactant's amount, in the order they were written
+    pub results: Vec<ConversionResult>,
+    /// The index of the result with the smallest answer, whose reactant runs out first
+    pub limiting: usize,
+}
+
+/// A cycle of conversions whose factors do not multiply back to 1, found by [`Registry::check_consistency`](crate::Registry::check_consistency)
+pub struct Inconsistency {
+    /// The ids of the units around the cycle, which starts and ends at the same unit
+    pub cycle: Vec<usize>,
+    /// The product of the conversion factors around the cycle
+    pub factor: f64,
+    /// The definition of each conversion along the cycle, like `$ 1 mile = 5280 feet`
+    pub definitions: Vec<String>,
+}
+
 pub struct Element {
     pub symbol: String,
+    pub name: String,
     pub atomic_number: usize,
-    pub molar_mass: f64
+    pub molar_mass: f64,
+    /// How far off the molar mass may be, in grams per mole
+    pub uncertainty: f64,
+    /// False for elements without a standard atomic weight, whose molar mass is the mass number
+    /// of their longest-lived isotope instead, written in brackets like `[98]`
+    pub standard_weight: bool,
+    /// Any other properties of the element in the order they were defined, like `("period", "4")` or `("block", "d")`
+    pub properties: Vec<(String, String)>,
 }
 
 impl Element {
-    pub fn new(symbol: String, atomic_number: usize, molar_mass: f64) -> Self {
-        Element { symbol, atomic_number, molar_mass }
+    pub fn new(symbol: String, name: String, atomic_number: usize, molar_mass: f64, uncertainty: f64) -> Self {
+        Element { symbol, name, atomic_number, molar_mass, uncertainty, standard_weight: true, properties: Vec::new() }
+    }
+
+    /// Returns the value of a property, like `"4"` for `period`
+    pub fn property(&self, key: &str) -> Option<&str> {
+        self.properties.iter().find(|(existing, _)| existing == key).map(|(_, value)| value.as_str())
     }
 }
\ No newline at end of file
@@ -0,0 +1,57 @@
+mod common;
+
+use unit_conversions::reaction::Reaction;
+use unit_conversions::ConversionError;
+use common::load_elements;
+
+fn coefficients(reaction: &Reaction) -> Vec<u32> {
+    reaction.species().map(|species| species.coefficient).collect()
+}
+
+#[test]
+fn reactions_get_the_smallest_whole_coefficients() {
+    let registry = load_elements();
+    let combustion = registry.balance("C3H8 + O2 -> CO2 + H2O").unwrap();
+    assert_eq!(coefficients(&combustion), vec![1, 5, 3, 4]);
+    assert_eq!(combustion.to_string(), "C3H8 + 5 O2 → 3 CO2 + 4 H2O");
+    let copper = registry.balance("Cu + HNO3 → Cu(NO3)2 + NO + H2O").unwrap();
+    assert_eq!(coefficients(&copper), vec![3, 8, 3, 2, 4]);
+    // coefficients that are written are kept when they balance the reaction
+    assert_eq!(coefficients(&registry.balance("4H2+2O2=4H2O").unwrap()), vec![4, 2, 4]);
+    assert_eq!(coefficients(&registry.balance("H2 + Cl2 -> 2 HCl").unwrap()), vec![1, 1, 2]);
+}
+
+#[test]
+fn charges_are_balanced_along_with_the_elements() {
+    let registry = load_elements();
+    let reaction = registry.balance("MnO4- + Fe^2+ + H+ -> Mn^2+ + Fe^3+ + H2O").unwrap();
+    assert_eq!(coefficients(&reaction), vec![1, 5, 8, 1, 5, 4]);
+    let half_reaction = registry.balance("Cr2O7^2- + H+ + e- -> Cr^3+ + H2O").unwrap();
+    assert_eq!(coefficients(&half_reaction), vec![1, 14, 6, 2, 7]);
+}
+
+#[test]
+fn reactions_that_cannot_be_balanced_are_rejected() {
+    let registry = load_elements();
+    for reaction in ["H2O -> CO2", "NaCl -> Na + Cl2 + H2O", "H2 + O2 -> H2O2 + H2O", "H2O + H2 -> O2"] {
+        assert!(matches!(registry.balance(reaction), Err(ConversionError::CannotBalance { .. })), "{reaction}");
+    }
+    assert!(matches!(registry.balance("H2 + O2"), Err(ConversionError::InvalidReaction { .. })));
+    assert!(matches!(registry.balance("H2 + Qq -> H2Qq"), Err(ConversionError::UnknownElement { .. })));
+}
+
+#[test]
+fn coefficients_that_do_not_balance_the_reaction_are_rejected() {
+    let registry = load_elements();
+    for reaction in ["C3H8 + 2 O2 -> 3 CO2 + 4 H2O", "4H2+2O2=2H2O", "H2 + O2 -> 1 H2O"] {
+        assert!(matches!(registry.balance(reaction), Err(ConversionError::CannotBalance { .. })), "{reaction}");
+    }
+}
+
+#[test]
+fn coefficients_that_are_not_written_are_filled_in() {
+    let registry = load_elements();
+    assert_eq!(coefficients(&registry.balance("C3H8 + 5 O2 -> CO2 + H2O").unwrap()), vec![1, 5, 3, 4]);
+    assert_eq!(coefficients(&registry.balance("2 C3H8 + O2 -> CO2 + H2O").unwrap()), vec![2, 10, 6, 8]);
+    assert_eq!(registry.balance("4H2+O2=H2O").unwrap().to_string(), "4 H2 + 2 O2 → 4 H2O");
+}
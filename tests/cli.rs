@@ -0,0 +1,125 @@
+use std::io::Write;
+use std::process::{Command, Output, Stdio};
+
+/// Runs the command-line program from the root of the crate, where it finds its files, and feeds it `input`
+fn run(args: &[&str], input: &str) -> Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_unit_conversions"))
+        .args(args)
+        .current_dir(env!("CARGO_MANIFEST_DIR"))
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.take().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stdout).into_owned()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8_lossy(&output.stderr).into_owned()
+}
+
+#[test]
+fn conversions_without_units_are_errors() {
+    for output in [run(&[], ";\n"), run(&[""], "")] {
+        assert_eq!(output.status.code(), Some(1), "{}", stderr(&output));
+        assert!(stderr(&output).contains("That conversion is impossible!"), "{}", stderr(&output));
+    }
+}
+
+#[test]
+fn negative_exponents_divide() {
+    let output = run(&["-a", "1 m^-1 : ft^-1"], "");
+    assert_eq!(stdout(&output).trim(), "0.3 / feet", "{}", stderr(&output));
+}
+
+#[test]
+fn one_conversion_is_printed_from_the_arguments() {
+    let output = run(&["1.3", "meter", ":", "feet"], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    let printed = stdout(&output);
+    let lines: Vec<&str> = printed.lines().filter(|line| !line.is_empty()).collect();
+    assert_eq!(lines.len(), 3, "{printed}");
+    assert!(lines[1].starts_with("1.3 meters") && lines[1].ends_with("= 4.3 feet"), "{}", lines[1]);
+    let output = run(&["--answer-only", "1.3 meter : feet"], "");
+    assert_eq!(stdout(&output), "4.3 feet\n");
+    let output = run(&["-u", "-a", "1.3 m : ft"], "");
+    assert_eq!(stdout(&output), "4.3 feet (4.2650918635170605 feet)\n");
+}
+
+#[test]
+fn the_exit_status_tells_failures_apart() {
+    let output = run(&["-a", "1 foo : m"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output).trim(), "Invalid Conversion: Unit 'foo' is not registered.");
+    let output = run(&["--bogus", "1 m : ft"], "");
+    assert_eq!(output.status.code(), Some(2));
+    assert!(stderr(&output).starts_with("Unknown option '--bogus'"), "{}", stderr(&output));
+}
+
+#[test]
+fn scripts_report_the_line_that_failed_and_keep_going() {
+    let script = "# widget|s, wd\n$ 1 widget = 3 m\n\n1 bar : m\n2 wd : m\nquit\n1 m : ft\n";
+    let output = run(&["-a"], script);
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stdout(&output), "Created new unit widgets\nCreated conversion between widgets and meters\n6 meters\n");
+    assert_eq!(stderr(&output), "line 4: Invalid Conversion: Unit 'bar' is not registered.\n");
+}
+
+#[test]
+fn scripts_can_be_read_from_a_file() {
+    let path = std::env::temp_dir().join(format!("unit_conversions_script_{}.txt", std::process::id()));
+    std::fs::write(&path, "1.0 m : ft\n1.0 ft : inch\n").unwrap();
+    let output = run(&["-a", "--script", path.to_str().unwrap()], "");
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "3.3 feet\n12 inches\n");
+    let output = run(&["-f", "no/such/script.txt"], "");
+    assert_eq!(output.status.code(), Some(1));
+}
+
+#[test]
+fn json_is_printed_one_line_per_result() {
+    let output = run(&["--json"], "1 m : ft\n1 foo : m\n");
+    let printed = stdout(&output);
+    let lines: Vec<&str> = printed.lines().collect();
+    assert_eq!(lines.len(), 2, "{printed}");
+    assert!(lines[0].starts_with(r#"{"status":"success""#), "{}", lines[0]);
+    assert!(lines[1].starts_with(r#"{"status":"error","error":{"kind":"unknown_unit""#), "{}", lines[1]);
+}
+
+#[test]
+fn check_fails_while_cycles_are_inconsistent() {
+    let output = run(&["check"], "");
+    assert_eq!(output.status.code(), Some(1));
+    assert!(stdout(&output).contains("kilometers → miles"), "{}", stdout(&output));
+    assert_eq!(stderr(&output).trim(), "Found 3 inconsistent cycles of conversions");
+    let output = run(&["check", "1e-3"], "");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+}
+
+#[test]
+fn check_is_only_run_as_a_whole_word() {
+    let output = run(&["-a"], "check 1e-3\ncheckmark\n");
+    assert!(stdout(&output).starts_with("Every cycle of conversions is consistent within 0.001"), "{}", stdout(&output));
+    assert_eq!(stderr(&output), "line 2: Invalid Conversion: Unit 'checkmark' is not registered.\n");
+}
+
+#[test]
+fn units_named_like_commands_can_still_be_converted() {
+    let output = run(&["-a"], "mm / s : m / s\nmm H2O\n");
+    assert_eq!(output.status.code(), Some(0), "{}", stderr(&output));
+    assert_eq!(stdout(&output), "0.001 meters / seconds\n18.0150 ± 0.0011 g/mol\n");
+    let output = run(&["-a", "mm", "/", "s", ":", "m", "/", "s"], "");
+    assert_eq!(stdout(&output), "0.001 meters / seconds\n", "{}", stderr(&output));
+}
+
+#[test]
+fn ans_continues_from_the_unrounded_answer() {
+    let output = run(&["-a"], "1.0 m : ft\nans : inch\n");
+    assert_eq!(String::from_utf8_lossy(&output.stdout), "3.3 feet\n39 inches\n", "{}", stderr(&output));
+}
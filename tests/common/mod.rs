@@ -0,0 +1,25 @@
+//! Fixtures shared by the integration tests. Not every test crate uses every fixture
+#![allow(dead_code)]
+
+use std::path::Path;
+
+use unit_conversions::Registry;
+
+/// A registry with the units of `conversions.txt` and the elements of `elements.txt`
+pub fn load_registry() -> Registry {
+    let mut registry = Registry::new();
+    registry.load_units_from_file(&root().join("conversions.txt")).unwrap();
+    registry.load_elements_from_file(&root().join("elements.txt")).unwrap();
+    registry
+}
+
+/// A registry with only the elements of `elements.txt`
+pub fn load_elements() -> Registry {
+    let mut registry = Registry::new();
+    registry.load_elements_from_file(&root().join("elements.txt")).unwrap();
+    registry
+}
+
+fn root() -> &'static Path {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+}
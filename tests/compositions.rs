@@ -0,0 +1,37 @@
+mod common;
+
+use unit_conversions::{ConversionError, Registry};
+use common::load_registry;
+
+#[test]
+fn units_are_broken_down_into_their_products() {
+    let mut registry = load_registry();
+    for (expression, expected) in [
+        ("1 acre : m^2", 4046.8564224),
+        ("1 L : cm^3", 1000f64),
+        ("1 J : kg*m^2/s^2", 1f64),
+        ("4046.8564224 m*m : acre", 1f64),
+    ] {
+        let answer = registry.convert_expression(expression).unwrap().answer;
+        assert!((answer - expected).abs() < 1e-6 * expected, "{expression} gave {answer}");
+    }
+}
+
+#[test]
+fn the_product_is_shown_as_a_step() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1 L : cm^3").unwrap();
+    let cubic_centimeters = registry.resolve_unit("cm").unwrap();
+    assert!(result.steps.iter().any(|step| step.top_ids() == [cubic_centimeters; 3]));
+}
+
+#[test]
+fn units_that_cannot_be_broken_down_to_the_same_number_are_rejected() {
+    let mut registry = Registry::new();
+    for name in ["a", "b"] {
+        registry.define_unit(name).unwrap();
+    }
+    registry.define_conversion("1 a = 2 b").unwrap();
+    let error = registry.convert_expression("1 a : b*b").err();
+    assert!(matches!(error, Some(ConversionError::MismatchedArity { .. })), "{error:?}");
+}
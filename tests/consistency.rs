@@ -0,0 +1,71 @@
+use unit_conversions::{ConversionError, Registry};
+
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    for name in ["a", "b", "c"] {
+        registry.define_unit(name).unwrap();
+    }
+    for definition in ["1 a = 2 b : exact", "1 b = 3 c : precision 1e-3"] {
+        registry.define_conversion(definition).unwrap();
+    }
+    registry
+}
+
+#[test]
+fn conflicting_conversions_are_rejected() {
+    let mut registry = registry();
+    let error = registry.define_conversion("1 a = 7 c").err();
+    assert!(matches!(error, Some(ConversionError::InconsistentConversion { .. })), "{error:?}");
+    // the rejected conversion must not change the cached factors
+    assert_eq!(registry.convert(1f64, "a", "c").unwrap().answer, 6f64);
+}
+
+#[test]
+fn conversions_within_their_precision_are_accepted() {
+    let mut registry = registry();
+    registry.define_conversion("1 a = 6.001 c : precision 1e-6").unwrap();
+    // the new conversion is more precise than the one through b, so it is used from now on
+    assert_eq!(registry.convert(1f64, "a", "c").unwrap().answer, 6.001);
+}
+
+#[test]
+fn components_are_merged_either_way() {
+    let mut registry = registry();
+    for name in ["x", "y"] {
+        registry.define_unit(name).unwrap();
+    }
+    registry.define_conversion("1 x = 10 y : exact").unwrap();
+    registry.define_conversion("1 y = 4 a : exact").unwrap();
+    let answer = registry.convert(1f64, "x", "c").unwrap().answer;
+    assert!((answer - 240f64).abs() < 1e-12);
+}
+
+#[test]
+fn exact_direct_conversions_are_not_made_less_precise_by_the_base() {
+    let mut registry = Registry::new();
+    for name in ["xx", "aa", "bb"] {
+        registry.define_unit(name).unwrap();
+    }
+    for definition in ["1 xx = 0.5 aa", "1 xx = 0.25 bb", "1 aa = 0.5 bb : exact"] {
+        registry.define_conversion(definition).unwrap();
+    }
+    let result = registry.convert_expression("1.000000000 aa : bb").unwrap();
+    assert_eq!(result.steps.len(), 1);
+    assert_eq!(result.significant_figures, Some(10));
+    assert_eq!(registry.answer_to_string(&result), "0.5000000000 bb");
+}
+
+#[test]
+fn cycles_that_do_not_multiply_to_one_are_found() {
+    let mut registry = registry();
+    assert!(registry.check_consistency(1e-9).is_empty());
+    registry.define_conversion("1 a = 6.001 c : precision 1e-6").unwrap();
+    let inconsistencies = registry.check_consistency(1e-9);
+    assert_eq!(inconsistencies.len(), 1);
+    let inconsistency = &inconsistencies[0];
+    assert_eq!(inconsistency.cycle.len(), 4);
+    assert_eq!(inconsistency.cycle.first(), inconsistency.cycle.last());
+    assert!((inconsistency.factor - 6.001 / 6f64).abs() < 1e-12 || (inconsistency.factor - 6f64 / 6.001).abs() < 1e-12, "{}", inconsistency.factor);
+    assert!(inconsistency.definitions.contains(&"1 a = 6.001 c : precision 1e-6".to_string()), "{:?}", inconsistency.definitions);
+    assert!(registry.check_consistency(1e-3).is_empty());
+}
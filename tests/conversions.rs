@@ -0,0 +1,24 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use common::load_registry;
+
+#[test]
+fn negative_exponents_move_units_to_the_other_side() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1 J : kg*m^2*s^-2").unwrap();
+    assert!((result.answer - 1f64).abs() < 1e-12, "{}", result.answer);
+    let result = registry.convert_expression("1 m^-1 : ft^-1").unwrap();
+    assert!((result.answer - 0.3048).abs() < 1e-12, "{}", result.answer);
+    let result = registry.convert_expression("2 / s^-1 : s").unwrap();
+    assert_eq!(result.answer, 2f64);
+}
+
+#[test]
+fn exponents_must_be_whole_numbers_other_than_zero() {
+    let mut registry = load_registry();
+    for (expression, exponent) in [("1 m^0 : ft", "0"), ("1 m^1.5 : ft", "1.5")] {
+        let error = registry.convert_expression(expression).err();
+        assert_eq!(error, Some(ConversionError::InvalidExponent { exponent: exponent.to_string() }), "{expression}");
+    }
+}
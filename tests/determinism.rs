@@ -0,0 +1,71 @@
+mod common;
+
+use unit_conversions::report::ConversionReport;
+use unit_conversions::Registry;
+use common::load_registry;
+
+const EXPRESSIONS: [&str; 8] = [
+    "1 mile : km",
+    "1.3 meter : feet",
+    "3 m / s : mi / hr",
+    "1 acre : m^2",
+    "1 month : hours",
+    "100 celsius : fahrenheit",
+    "2 gallon : L",
+    "12 g [H2O] : mol",
+];
+
+fn reports(registry: &mut Registry) -> Vec<ConversionReport> {
+    EXPRESSIONS.iter()
+        .map(|expression| {
+            let result = registry.convert_expression(expression).unwrap();
+            registry.report(&result)
+        })
+        .collect()
+}
+
+#[test]
+fn fresh_registries_choose_the_same_steps() {
+    // every registry hashes its maps with a different seed, so this catches paths that depend on hash order
+    let expected = reports(&mut load_registry());
+    for _ in 0..20 {
+        assert_eq!(reports(&mut load_registry()), expected);
+    }
+}
+
+#[test]
+fn repeated_conversions_choose_the_same_steps() {
+    let mut registry = load_registry();
+    let expected = reports(&mut registry);
+    for _ in 0..20 {
+        assert_eq!(reports(&mut registry), expected);
+    }
+}
+
+#[test]
+fn equally_precise_paths_go_through_the_lowest_id() {
+    for order in [["b", "c"], ["c", "b"]] {
+        let mut registry = Registry::new();
+        for name in ["a", order[0], order[1], "d"] {
+            registry.define_unit(name).unwrap();
+        }
+        // a -> b -> d and a -> c -> d are both exact and two steps long
+        for definition in ["1 a = 2 b : exact", "1 a = 2 c : exact", "1 b = 3 d : exact", "1 c = 3 d : exact"] {
+            registry.define_conversion(definition).unwrap();
+        }
+        let result = registry.convert(1f64, "a", "d").unwrap();
+        let report = registry.report(&result);
+        assert_eq!(report.steps[0].top_units, [order[0]]);
+        assert_eq!(result.answer, 6f64);
+    }
+}
+
+#[test]
+fn exact_paths_are_preferred_over_shorter_ones() {
+    let mut registry = load_registry();
+    let result = registry.convert(1f64, "mile", "km").unwrap();
+    let report = registry.report(&result);
+    let path: Vec<&str> = report.steps.iter().map(|step| step.top_units[0].as_str()).collect();
+    assert_eq!(path, ["feet", "inches", "centimeters", "meters", "kilometers"]);
+    assert!((result.answer - 1.609344).abs() < 1e-12);
+}
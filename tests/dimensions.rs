@@ -0,0 +1,42 @@
+mod common;
+
+use unit_conversions::{ConversionError, Registry};
+use common::load_registry;
+
+#[test]
+fn dimensions_are_derived_from_the_conversions() {
+    let registry = load_registry();
+    let id = |alias| registry.unit_id(alias).unwrap();
+    let length = registry.dimension_of(&[id("m")], &[]).unwrap().unwrap();
+    assert_eq!(registry.dimension_of(&[id("ft")], &[]), Ok(Some(length)));
+    assert_eq!(registry.dimension_of(&[id("mile")], &[id("hour")]).unwrap().unwrap().to_string(), "length·time⁻¹");
+}
+
+#[test]
+fn different_quantities_are_rejected_before_searching() {
+    let mut registry = load_registry();
+    let error = registry.convert_expression("1 m/s : kg").err();
+    assert!(matches!(error, Some(ConversionError::IncompatibleDimensions { .. })), "{error:?}");
+    assert_eq!(error.unwrap().to_string(), "Cannot convert length·time⁻¹ to mass");
+}
+
+#[test]
+fn units_of_different_quantities_cannot_be_linked() {
+    let mut registry = Registry::new();
+    registry.define_unit("meter : length").unwrap();
+    registry.define_unit("second : time").unwrap();
+    let error = registry.define_conversion("1 meter = 1 second").err();
+    assert!(matches!(error, Some(ConversionError::ConflictingDimensions { .. })), "{error:?}");
+    let error = registry.define_unit("widget : flavor").err();
+    assert_eq!(error, Some(ConversionError::UnknownQuantity { quantity: "flavor".to_string() }));
+}
+
+#[test]
+fn exponents_that_overflow_are_rejected() {
+    let mut registry = load_registry();
+    let error = registry.convert_expression("1 m^100 * m^100 : ft^100 * ft^100").err();
+    assert!(matches!(error, Some(ConversionError::ExponentOutOfRange { .. })), "{error:?}");
+    let mut registry = Registry::new();
+    let error = registry.define_unit("foo : length^100 * length^100").err();
+    assert_eq!(error, Some(ConversionError::ExponentOutOfRange { expression: "length^100 * length^100".to_string() }));
+}
@@ -0,0 +1,43 @@
+mod common;
+
+use unit_conversions::{ConversionError, Registry};
+use common::load_elements;
+
+#[test]
+fn every_element_is_loaded_with_its_data() {
+    let registry = load_elements();
+    assert_eq!(registry.elements().count(), 118);
+    assert!(registry.elements().map(|element| element.atomic_number).eq(1..=118));
+    let iron = registry.element_by_alias("Iron").unwrap();
+    assert_eq!((iron.symbol.as_str(), iron.atomic_number, iron.molar_mass, iron.uncertainty), ("Fe", 26, 55.845, 0.002));
+    assert_eq!(iron.property("group"), Some("8"));
+    assert_eq!(iron.property("block"), Some("d"));
+    assert_eq!(registry.element(118).unwrap().name, "Oganesson");
+    assert_eq!(registry.element_by_alias("Aluminum").unwrap().symbol, "Al");
+}
+
+#[test]
+fn elements_without_a_standard_weight_use_a_mass_number() {
+    let registry = load_elements();
+    let technetium = registry.element_by_alias("Tc").unwrap();
+    assert!(!technetium.standard_weight);
+    assert_eq!(technetium.molar_mass, 98f64);
+    assert!(registry.element_by_alias("U").unwrap().standard_weight);
+}
+
+#[test]
+fn atomic_numbers_must_be_unique_and_contiguous() {
+    let mut registry = load_elements();
+    assert!(matches!(
+        registry.define_element("26 Xx, Examplium = 10.0"),
+        Err(ConversionError::DuplicateAtomicNumber { atomic_number: 26, existing }) if existing == "Iron"
+    ));
+    // without a number, an element comes after the last one
+    assert_eq!(registry.define_element("Xx, Examplium = 10.0"), Ok(119));
+
+    let path = std::env::temp_dir().join(format!("elements-with-a-gap-{}.txt", std::process::id()));
+    std::fs::write(&path, "1 H, Hydrogen = 1.008\n3 Li, Lithium = 6.94\n").unwrap();
+    let result = Registry::new().load_elements_from_file(&path);
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(result, Err(ConversionError::UnknownAtomicNumber { atomic_number: 2 }));
+}
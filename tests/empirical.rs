@@ -0,0 +1,43 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use common::load_elements;
+
+#[test]
+fn mass_percents_give_the_empirical_and_molecular_formula() {
+    let registry = load_elements();
+    let report = registry.empirical_formula("C 40.0%, H 6.7%, O 53.3% : 180.16 g/mol", 0.1).unwrap();
+    let counts: Vec<(&str, u32)> = report.elements.iter().map(|amount| (amount.symbol.as_str(), amount.count)).collect();
+    assert_eq!(counts, vec![("C", 1), ("H", 2), ("O", 1)]);
+    assert_eq!(report.empirical_formula, "CH₂O");
+    assert_eq!(report.empirical_molar_mass, 30.026);
+    assert_eq!(report.molecular_multiple, Some(6));
+    assert_eq!(report.molecular_formula.as_deref(), Some("C₆H₁₂O₆"));
+}
+
+#[test]
+fn ratios_are_multiplied_until_they_are_whole() {
+    let registry = load_elements();
+    let report = registry.empirical_formula("Fe 2.233 g, Oxygen 0.960 g", 0.1).unwrap();
+    assert_eq!(report.multiplier, 2);
+    assert_eq!(report.empirical_formula, "Fe₂O₃");
+    assert_eq!(report.elements[1].significant_figures, Some(3));
+    assert!(report.molecular_formula.is_none());
+}
+
+#[test]
+fn compositions_that_do_not_fit_are_rejected() {
+    let registry = load_elements();
+    assert!(matches!(registry.empirical_formula("C 1 g, H 1.37 g", 0.01), Err(ConversionError::NoWholeNumberRatio { .. })));
+    assert!(matches!(registry.empirical_formula("C 40.0%, H 6.7%, O 53.3% : 100", 0.1), Err(ConversionError::MolarMassNotMultiple { .. })));
+    assert!(matches!(registry.empirical_formula("C 40.0%, H 6.7 g", 0.1), Err(ConversionError::InvalidComposition { .. })));
+    assert!(matches!(registry.empirical_formula("C 40.0%, Qq 60.0%", 0.1), Err(ConversionError::UnknownElement { position: 9, .. })));
+}
+
+#[test]
+fn counts_too_large_to_mean_anything_are_rejected() {
+    let registry = load_elements();
+    assert!(matches!(registry.empirical_formula("Fe 1%, O 1e-300%", 0.1), Err(ConversionError::NoWholeNumberRatio { .. })));
+    assert!(matches!(registry.empirical_formula("Fe 1%, O 1e-4%", 0.1), Err(ConversionError::NoWholeNumberRatio { .. })));
+    assert!(matches!(registry.empirical_formula("C 40.0%, H 6.7%, O 53.3% : 1e300", 0.1), Err(ConversionError::MolarMassNotMultiple { .. })));
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use common::load_registry;
+
+#[test]
+fn bad_expressions_are_errors_instead_of_panics() {
+    let mut registry = load_registry();
+    let missing_start = ConversionError::MissingUnits { side: "starting".to_string() };
+    for (expression, expected) in [
+        ("", missing_start.clone()),
+        (";", missing_start),
+        ("1 m : 2 ft", ConversionError::MisplacedNumber),
+        ("12 g [H2O : mol", ConversionError::UnclosedBracket),
+        ("1 m ; ft", ConversionError::UnexpectedCharacter { character: ';' }),
+        ("1 g [Qq] : mol", ConversionError::UnknownElement { symbol: "Qq".to_string(), position: 0 }),
+        ("1 smoot : m", ConversionError::UnknownUnit { alias: "smoot".to_string() }),
+        ("1 m^200 : ft^200", ConversionError::ExponentOutOfRange { expression: "m^200".to_string() }),
+        ("1 m^4000000000 : ft", ConversionError::InvalidExponent { exponent: "4000000000".to_string() }),
+        ("1 m^100 * m^100 : ft^100 * ft^100", ConversionError::ExponentOutOfRange { expression: "meters^200".to_string() }),
+    ] {
+        assert_eq!(registry.convert_expression(expression).err(), Some(expected), "{expression:?}");
+    }
+}
+
+#[test]
+fn bad_definitions_are_errors_instead_of_panics() {
+    let mut registry = load_registry();
+    for definition in ["1 m 100 cm", "1 = 2 m"] {
+        let error = registry.define_conversion(definition).err();
+        assert!(matches!(error, Some(ConversionError::MalformedDefinition { .. })), "{definition:?} gave {error:?}");
+    }
+    for definition in ["foo : length^100 * length^100", "foo : length^200"] {
+        let error = registry.define_unit(definition).err();
+        assert!(matches!(error, Some(ConversionError::ExponentOutOfRange { .. } | ConversionError::InvalidExponent { .. })), "{definition:?} gave {error:?}");
+    }
+    for definition in ["", "Qq, Quxium"] {
+        let error = registry.define_element(definition).err();
+        assert!(matches!(error, Some(ConversionError::MalformedDefinition { .. })), "{definition:?} gave {error:?}");
+    }
+}
+
+#[test]
+fn errors_print_the_messages_of_the_repl() {
+    let mut registry = load_registry();
+    let error = registry.convert_expression("1 smoot : m").err().unwrap();
+    assert_eq!(error.to_string(), "Invalid Conversion: Unit 'smoot' is not registered.");
+    let error = registry.convert_expression("12 g [H2O : mol").err().unwrap();
+    assert_eq!(error.to_string(), "Opening brace without closing brace!");
+}
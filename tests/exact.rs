@@ -0,0 +1,59 @@
+mod common;
+
+use unit_conversions::rational::{self, Rational};
+use common::load_registry;
+
+fn fraction(numerator: i64, denominator: i64) -> Rational {
+    Rational::new(numerator.into(), denominator.into())
+}
+
+#[test]
+fn decimals_are_parsed_into_the_fractions_they_are_written_as() {
+    assert_eq!(rational::parse_decimal("1.60934"), Some(fraction(160934, 100000)));
+    assert_eq!(rational::parse_decimal("-2.5e-3"), Some(fraction(-1, 400)));
+    assert_eq!(rational::parse_decimal("6.02214076e23"), Some(Rational::from_integer("602214076000000000000000".parse().unwrap())));
+    assert_eq!(rational::parse_decimal("inf"), None);
+}
+
+#[test]
+fn chains_of_exact_factors_are_not_rounded_along_the_way() {
+    let mut registry = load_registry();
+    let result = registry.convert(1f64, "mile", "inch").unwrap();
+    assert_eq!(result.exact_answer, Some(fraction(63360, 1)));
+    assert_eq!(result.answer, 63360f64);
+    assert_eq!(registry.exact_answer_to_string(&result).unwrap(), "63360/1 inches");
+}
+
+#[test]
+fn offsets_are_exact_too() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("-40 fahrenheit : celsius").unwrap();
+    assert_eq!(result.exact_answer, Some(fraction(-40, 1)));
+    let result = registry.convert_expression("1 m : ft").unwrap();
+    assert_eq!(result.exact_answer, Some(fraction(1250, 381)));
+}
+
+#[test]
+fn values_that_are_not_decimals_have_no_exact_answer() {
+    let mut registry = load_registry();
+    let result = registry.convert(f64::MAX * 2f64, "m", "cm").unwrap();
+    assert_eq!(result.exact_answer, None);
+}
+
+#[test]
+fn fractions_are_written_as_decimals_when_they_end() {
+    assert_eq!(rational::to_exact_string(&fraction(1609344, 1000000)), "1.609344");
+    assert_eq!(rational::to_exact_string(&fraction(-1, 400)), "-0.0025");
+    assert_eq!(rational::to_exact_string(&fraction(-40, 1)), "-40");
+    assert_eq!(rational::to_exact_string(&fraction(160, 9)), "160/9");
+}
+
+#[test]
+fn steps_show_their_exact_factors() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("100 fahrenheit : celsius").unwrap();
+    let step = &result.steps[0];
+    assert_eq!(step.get_top(registry.unit_ids()), "1 celsius");
+    assert_eq!(step.get_bottom(registry.unit_ids()), "1.8 fahrenheit");
+    assert_eq!(step.get_offset(registry.unit_ids()).unwrap(), "- 160/9 celsius");
+}
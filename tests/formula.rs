@@ -0,0 +1,65 @@
+mod common;
+
+use unit_conversions::formula::Formula;
+use unit_conversions::ConversionError;
+use common::load_registry;
+
+/// The atomic numbers of the few elements that these tests use
+fn atomic_number(symbol: &str) -> Option<usize> {
+    ["H", "C", "N", "O", "P", "S", "Ca", "Cu"].iter()
+        .position(|known| *known == symbol)
+        .map(|index| [1, 6, 7, 8, 15, 16, 20, 29][index])
+}
+
+fn parse(formula: &str) -> Result<Formula, ConversionError> {
+    Formula::parse(formula, atomic_number)
+}
+
+#[test]
+fn groups_multiply_everything_inside_them() {
+    assert_eq!(parse("Ca(OH)2").unwrap().elements, vec![(20, 1), (8, 2), (1, 2)]);
+    assert_eq!(parse("(NH₄)₃PO₄").unwrap().elements, vec![(7, 3), (1, 12), (15, 1), (8, 4)]);
+    assert_eq!(parse("CH3COOH").unwrap().elements, vec![(6, 2), (1, 4), (8, 2)]);
+}
+
+#[test]
+fn hydrates_and_charges_are_read() {
+    let hydrate = parse("CuSO4·5H2O").unwrap();
+    assert_eq!(hydrate.elements, vec![(29, 1), (16, 1), (8, 9), (1, 10)]);
+    assert_eq!(parse("CuSO4 * 5H2O").unwrap(), hydrate);
+    assert_eq!(hydrate.written, "CuSO₄·5H₂O");
+    for sulfate in ["SO4^2-", "SO4^-2", "SO₄²⁻"] {
+        let formula = parse(sulfate).unwrap();
+        assert_eq!(formula.charge, -2, "{sulfate}");
+        assert_eq!(formula.charge_superscript(), "²⁻");
+        assert_eq!(formula.written, "SO₄²⁻");
+    }
+    assert_eq!(parse("NH4+").unwrap().charge, 1);
+    assert_eq!(parse("H2O").unwrap().charge_superscript(), "");
+}
+
+#[test]
+fn errors_point_at_the_bad_character() {
+    let position = |formula: &str| match parse(formula) {
+        Err(ConversionError::InvalidFormula { position, .. }) => Some(position),
+        _ => None,
+    };
+    assert_eq!(position("Ca(OH2"), Some(6));
+    assert_eq!(position("H2O)"), Some(3));
+    assert_eq!(position("()"), Some(1));
+    assert_eq!(position("SO4^2"), Some(5));
+    assert_eq!(position(""), Some(0));
+    assert!(matches!(parse("H0"), Err(ConversionError::InvalidSubscript { position: 1, .. })));
+    assert!(matches!(parse("CO00"), Err(ConversionError::InvalidSubscript { position: 2, .. })));
+    assert!(matches!(parse("Xy2"), Err(ConversionError::UnknownElement { symbol, position: 0 }) if symbol == "Xy"));
+    assert!(matches!(parse("CaXy"), Err(ConversionError::UnknownElement { symbol, position: 2 }) if symbol == "Xy"));
+}
+
+#[test]
+fn ions_and_hydrates_get_their_own_units() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1 mol [SO4^2-] : g").unwrap();
+    assert!(registry.answer_to_string(&result).ends_with("grams [SO₄²⁻]"));
+    let result = registry.convert_expression("1 mol [CuSO4·5H2O] : g").unwrap();
+    assert!((result.answer - 249.68).abs() < 0.01, "{}", result.answer);
+}
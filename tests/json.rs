@@ -0,0 +1,36 @@
+mod common;
+
+use serde_json::{json, Value};
+use common::load_registry;
+
+fn parse(json: &str) -> Value {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn results_report_every_step() {
+    let mut registry = load_registry();
+    let json = registry.report_expression("1.3 m : ft").to_json();
+    // steps that are turned around have no offset either, rather than one of -0
+    assert!(!json.contains("-0.0"), "{json}");
+    let report = parse(&json);
+    assert_eq!(report["status"], "success");
+    assert_eq!(report["value"], 1.3);
+    assert_eq!(report["starting_units"]["numerators"], json!(["meters"]));
+    assert_eq!(report["ending_units"]["numerators"], json!(["feet"]));
+    let steps = report["steps"].as_array().unwrap();
+    assert_eq!(steps.first().unwrap()["bottom_units"], json!(["meters"]));
+    assert_eq!(steps.last().unwrap()["top_units"], json!(["feet"]));
+    assert!(steps.iter().all(|step| step["offset"].as_f64().is_some_and(|offset| offset == 0f64 && offset.is_sign_positive())), "{steps:?}");
+    assert_eq!(report["answer_text"], "4.3 feet");
+    assert_eq!(report["exact_answer"], "1625/381");
+}
+
+#[test]
+fn errors_are_reported_with_their_kind() {
+    let mut registry = load_registry();
+    let report = parse(&registry.report_expression("1 foo : m").to_json());
+    assert_eq!(report["status"], "error");
+    assert_eq!(report["error"], json!({ "kind": "unknown_unit", "alias": "foo" }));
+    assert_eq!(report["message"], "Invalid Conversion: Unit 'foo' is not registered.");
+}
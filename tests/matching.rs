@@ -0,0 +1,58 @@
+use unit_conversions::{ConversionError, Registry};
+
+/// Two unrelated families of units: `a` converts to `x`, and `b` converts to both `x` and `y`
+fn registry() -> Registry {
+    let mut registry = Registry::new();
+    for name in ["a", "b", "x", "y", "lonely"] {
+        registry.define_unit(name).unwrap();
+    }
+    for definition in ["1 a = 2 x", "1 b = 3 y", "1 y = 5 x"] {
+        registry.define_conversion(definition).unwrap();
+    }
+    registry
+}
+
+#[test]
+fn every_start_is_matched_with_its_own_end() {
+    let mut registry = registry();
+    // pairing `b` with the first end it reaches, `x`, would leave `a` with nothing to convert to
+    let result = registry.convert_expression("1 b * a : x * y").unwrap();
+    assert_eq!(result.answer, 3f64 * 2f64);
+}
+
+#[test]
+fn a_partial_matching_is_an_error() {
+    let mut registry = registry();
+    let error = registry.convert_expression("1 a * lonely : x * y").err();
+    assert!(matches!(error, Some(ConversionError::UnmatchedUnits { .. })), "{error:?}");
+}
+
+#[test]
+fn identical_units_need_no_steps() {
+    let mut registry = registry();
+    let result = registry.convert_expression("4 a / b : a / b").unwrap();
+    assert!(result.steps.is_empty());
+    assert_eq!(result.answer, 4f64);
+}
+
+#[test]
+fn the_units_that_could_not_be_connected_are_reported() {
+    let mut registry = registry();
+    match registry.convert_expression("1 a * lonely / b : x * y / x") {
+        Err(ConversionError::UnmatchedUnits { unmatched, .. }) => assert_eq!(unmatched, ["lonely"]),
+        Err(error) => panic!("Expected unmatched units, found {error:?}"),
+        Ok(_) => panic!("Expected unmatched units, found an answer"),
+    }
+}
+
+#[test]
+fn conversions_need_units_on_both_sides() {
+    let mut registry = registry();
+    for (expression, side) in [(";", "starting"), ("", "starting"), ("2 a :", "ending")] {
+        let error = registry.convert_expression(expression).err();
+        assert_eq!(error, Some(ConversionError::MissingUnits { side: side.to_string() }), "{expression}");
+    }
+    let result = registry.convert_expression("2 / a : / x").unwrap();
+    assert_eq!(result.answer, 1f64);
+    assert_eq!(registry.answer_to_string(&result), "1 / x");
+}
@@ -0,0 +1,27 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use common::{load_elements, load_registry};
+
+#[test]
+fn every_element_gets_its_share_of_the_molar_mass() {
+    let registry = load_elements();
+    let report = registry.molar_mass("Ca(OH)2").unwrap();
+    assert_eq!(report.formula, "Ca(OH)₂");
+    assert!((report.molar_mass - 74.092).abs() < 1e-9);
+    let shares: Vec<(&str, u32, f64)> = report.elements.iter().map(|share| (share.symbol.as_str(), share.count, share.mass)).collect();
+    assert_eq!(shares, vec![("Ca", 1, 40.078), ("O", 2, 31.998), ("H", 2, 2.016)]);
+    let percents: f64 = report.elements.iter().map(|share| share.mass_percent).sum();
+    assert!((percents - 100f64).abs() < 1e-9);
+    assert!((report.elements[0].mass_percent - 54.092).abs() < 1e-3);
+}
+
+#[test]
+fn the_molar_mass_matches_the_conversion_factor() {
+    let mut registry = load_registry();
+    let report = registry.molar_mass("CuSO4·5H2O").unwrap();
+    assert_eq!(report.formula, "CuSO₄·5H₂O");
+    let result = registry.convert_expression("1 mol [CuSO4·5H2O] : g").unwrap();
+    assert!((report.molar_mass - result.answer).abs() < 1e-9);
+    assert!(matches!(registry.molar_mass("Qq"), Err(ConversionError::UnknownElement { .. })));
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use unit_conversions::{ConversionError, Registry};
+use common::load_registry;
+
+#[test]
+fn units_defined_as_products_are_exact() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1.000000000 acre : ft^2").unwrap();
+    assert_eq!(result.significant_figures, Some(10));
+    assert_eq!(registry.answer_to_string(&result), "43560.00000 feet * feet");
+    for expression in ["1.000000000 hectare : m^2", "1.000000000 J : kg * m^2 / s^2", "1.000000000 cubic foot : inch^3"] {
+        let result = registry.convert_expression(expression).unwrap();
+        assert_eq!(result.significant_figures, Some(10), "{expression}");
+    }
+}
+
+#[test]
+fn the_most_precise_path_is_used() {
+    let mut registry = Registry::new();
+    for name in ["a", "b", "c"] {
+        registry.define_unit(name).unwrap();
+    }
+    for definition in ["1 a = 6.01 c : precision 1e-2", "1 a = 2 b : exact", "1 b = 3 c : exact"] {
+        registry.define_conversion(definition).unwrap();
+    }
+    let result = registry.convert_expression("1.0000 a : c").unwrap();
+    assert_eq!(result.answer, 6f64);
+    assert_eq!(result.significant_figures, Some(5));
+}
+
+#[test]
+fn exact_factors_are_preferred_over_rounded_ones() {
+    let mut registry = load_registry();
+    let answer = registry.convert_expression("1 mile : km").unwrap().answer;
+    assert!((answer - 1.609344).abs() < 1e-12, "{answer}");
+}
+
+#[test]
+fn equally_precise_paths_are_compared_by_their_length() {
+    let mut registry = Registry::new();
+    for name in ["a", "b", "c"] {
+        registry.define_unit(name).unwrap();
+    }
+    for definition in ["1 a = 2 b : exact", "1 b = 3 c : exact", "1 a = 6 c : exact"] {
+        registry.define_conversion(definition).unwrap();
+    }
+    assert_eq!(registry.convert_expression("1 a : c").unwrap().steps.len(), 1);
+}
+
+#[test]
+fn imprecise_factors_limit_the_significant_figures() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1.000000000 month : days").unwrap();
+    assert_eq!(result.significant_figures, Some(6));
+    assert_eq!(registry.answer_to_string(&result), "30.4167 days");
+}
+
+#[test]
+fn precisions_must_be_exact_or_a_relative_error() {
+    let mut registry = Registry::new();
+    for name in ["a", "b"] {
+        registry.define_unit(name).unwrap();
+    }
+    for attribute in ["roughly", "precision lots", "precision -1e-3"] {
+        let error = registry.define_conversion(&format!("1 a = 2 b : {attribute}")).err();
+        assert!(matches!(error, Some(ConversionError::MalformedDefinition { .. })), "{attribute} gave {error:?}");
+    }
+}
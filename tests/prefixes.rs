@@ -0,0 +1,46 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use unit_conversions::prefixes::split_prefix;
+use common::load_registry;
+
+#[test]
+fn prefixed_units_are_created_with_their_names_and_symbols() {
+    let mut registry = load_registry();
+    for (expression, expected) in [
+        ("1 km : m", 1e3),
+        ("1 kilometer : millimeter", 1e6),
+        ("1 µs : ns", 1e3),
+        ("1 us : nanoseconds", 1e3),
+        ("1 kg : mg", 1e6),
+        ("nanometer : m", 1e-9),
+    ] {
+        let answer = registry.convert_expression(expression).unwrap().answer;
+        assert!((answer - expected).abs() < 1e-9 * expected, "{expression} gave {answer}");
+    }
+    let kilometers = registry.resolve_unit("km").unwrap();
+    assert_eq!(registry.unit(kilometers).unwrap().get_name(), "kilometers");
+}
+
+#[test]
+fn units_of_data_also_accept_binary_prefixes() {
+    let mut registry = load_registry();
+    assert_eq!(registry.convert_expression("1 KiB : B").unwrap().answer, 1024f64);
+    assert_eq!(registry.convert_expression("1 mebibyte : kibibyte").unwrap().answer, 1024f64);
+    assert_eq!(registry.convert_expression("1 kB : bits").unwrap().answer, 8000f64);
+}
+
+#[test]
+fn units_only_accept_the_prefixes_they_are_marked_with() {
+    let mut registry = load_registry();
+    for alias in ["kilominute", "KiL"] {
+        let error = registry.convert_expression(&format!("1 {alias} : s")).err();
+        assert_eq!(error, Some(ConversionError::UnknownUnit { alias: alias.to_string() }));
+    }
+}
+
+#[test]
+fn longer_prefixes_are_tried_first() {
+    let splits: Vec<(&str, &str)> = split_prefix("dam").into_iter().map(|(prefix, rest)| (prefix.name, rest)).collect();
+    assert_eq!(splits, [("deca", "m"), ("deci", "am")]);
+}
@@ -0,0 +1,29 @@
+mod common;
+
+use unit_conversions::{ConversionError, Registry};
+use common::load_registry;
+
+#[test]
+fn units_and_conversions_can_be_defined_without_files() {
+    let mut registry = Registry::new();
+    let furlong = registry.define_unit("# furlong|s, fur").unwrap();
+    let chain = registry.define_unit("chain|s, ch").unwrap();
+    assert_eq!(registry.define_conversion("$ 1 furlong = 10 chain").unwrap(), (furlong, chain));
+    assert_eq!(registry.unit_id("fur"), Some(furlong));
+    assert_eq!(registry.unit(chain).unwrap().get_name(), "chains");
+    assert_eq!(registry.convert(3f64, "fur", "ch").unwrap().answer, 30f64);
+    let result = registry.convert_expression("2.5 furlongs : chains").unwrap();
+    assert_eq!(registry.quantity_to_string(result.answer, &result.query.ending_numers, &result.query.ending_denoms), "25 chains");
+}
+
+#[test]
+fn registries_are_independent_of_each_other() {
+    let mut first = load_registry();
+    let mut second = Registry::new();
+    second.define_unit("meter|s, m").unwrap();
+    assert!(first.convert(1f64, "m", "ft").is_ok());
+    assert_eq!(second.convert(1f64, "m", "ft").err(), Some(ConversionError::UnknownUnit { alias: "ft".to_string() }));
+    first.clear();
+    assert_eq!(first.units().count(), 0);
+    assert!(first.element(1).is_none());
+}
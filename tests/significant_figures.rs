@@ -0,0 +1,75 @@
+mod common;
+
+use unit_conversions::significant_figures;
+use common::load_registry;
+
+#[test]
+fn written_numbers_are_counted_like_in_chemistry() {
+    for (text, figures) in [("1.30", 3), ("0.00130", 3), ("1300", 2), ("1300.", 4), ("1.30e5", 3), ("-4", 1), ("0", 1), ("0.0", 1), ("0.00", 2)] {
+        assert_eq!(significant_figures::count(text), Some(figures), "{text}");
+    }
+    assert_eq!(significant_figures::count("inf"), None);
+}
+
+#[test]
+fn rounded_answers_show_their_significant_figures() {
+    for (value, figures, text) in [(4.2650918, 3, "4.27"), (9.996, 3, "10.0"), (299.7, 3, "300."), (12345f64, 3, "12300"), (6.0221e23, 3, "6.02e23")] {
+        let formatted = significant_figures::format(value, figures);
+        assert_eq!(formatted, text);
+        assert_eq!(significant_figures::count(&formatted), Some(figures), "{formatted}");
+    }
+}
+
+#[test]
+fn exact_factors_keep_the_figures_of_the_value() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1.30 m : ft").unwrap();
+    assert_eq!(result.significant_figures, Some(3));
+    assert_eq!(registry.answer_to_string(&result), "4.27 feet");
+    assert_eq!(registry.unrounded_answer_to_string(&result), "4.2650918635170605 feet");
+}
+
+#[test]
+fn imprecise_factors_limit_the_figures() {
+    let mut registry = load_registry();
+    registry.define_unit("# cubit").unwrap();
+    registry.define_conversion("$ 1 cubit = 18 inches : precision 1e-2").unwrap();
+    let result = registry.convert_expression("2.0000 cubit : inches").unwrap();
+    assert_eq!(result.significant_figures, Some(2));
+    assert_eq!(registry.answer_to_string(&result), "36 inches");
+}
+
+#[test]
+fn values_that_were_not_written_are_exact() {
+    let mut registry = load_registry();
+    let result = registry.convert(1f64, "mile", "inch").unwrap();
+    assert_eq!(result.significant_figures, None);
+    assert_eq!(registry.answer_to_string(&result), "63360 inches");
+}
+
+#[test]
+fn offsets_keep_the_decimal_places_of_the_value() {
+    let mut registry = load_registry();
+    for (expression, answer) in [
+        ("0 celsius : kelvin", "273 kelvin"),
+        ("1 kelvin : celsius", "-272 celsius"),
+        ("100 celsius : fahrenheit", "212 fahrenheit"),
+        ("37.0 celsius : fahrenheit", "98.6 fahrenheit"),
+    ] {
+        let result = registry.convert_expression(expression).unwrap();
+        assert_eq!(registry.answer_to_string(&result), answer, "{expression}");
+    }
+}
+
+#[test]
+fn previous_answers_are_only_rounded_for_display() {
+    let mut registry = load_registry();
+    let feet = registry.convert_expression("1.0 m : ft").unwrap();
+    assert_eq!(registry.answer_to_string(&feet), "3.3 feet");
+    let inches = registry.convert_answer(&feet, " : inch").unwrap();
+    // 3.3 feet would give 39.6 inches, which rounds to 40.
+    assert_eq!(registry.answer_to_string(&inches), "39 inches");
+    let kelvin = registry.convert_expression("0 celsius : kelvin").unwrap();
+    let fahrenheit = registry.convert_answer(&kelvin, " : fahrenheit").unwrap();
+    assert_eq!(registry.answer_to_string(&fahrenheit), "32 fahrenheit");
+}
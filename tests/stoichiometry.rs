@@ -0,0 +1,54 @@
+mod common;
+
+use unit_conversions::ConversionError;
+use common::load_registry;
+
+const COMBUSTION: &str = "using C3H8 + O2 -> CO2 + H2O";
+
+#[test]
+fn the_mole_ratio_is_a_step_of_the_conversion() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression(&format!("12.0 g [C3H8] : g [CO2] {COMBUSTION}")).unwrap();
+    let expected = 12.0 / 44.097 * 3.0 * 44.009;
+    assert!((result.answer - expected).abs() < 1e-9, "{}", result.answer);
+    assert_eq!(result.steps.len(), 3);
+    assert_eq!((result.steps[1].top_value(), result.steps[1].bottom_value()), (3f64, 1f64));
+    assert_eq!(registry.answer_to_string(&result), "35.9 grams [CO₂]");
+}
+
+#[test]
+fn reactions_do_not_link_their_substances_for_good() {
+    let mut registry = load_registry();
+    registry.convert_expression("1 mol [C] : mol [CO2] using C + O2 -> CO2").unwrap();
+    // a different ratio between the same substances is not inconsistent with the first reaction
+    let result = registry.convert_expression("1 mol [C] : mol [O2] using C + O2 -> CO").unwrap();
+    assert_eq!(result.answer, 0.5);
+    assert!(registry.convert_expression("1 mol [C] : mol [CO2]").is_err());
+}
+
+#[test]
+fn substances_outside_the_reaction_are_rejected() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression(&format!("12 g [C3H8] : g [NaCl] {COMBUSTION}"));
+    assert!(matches!(result, Err(ConversionError::NotInReaction { substance, .. }) if substance == "NaCl"));
+}
+
+#[test]
+fn the_reactant_that_makes_the_least_is_limiting() {
+    let mut registry = load_registry();
+    let limiting = registry.limiting_reagent(&format!("12.0 g [C3H8], 20.0 g [O2] : g [CO2] {COMBUSTION}")).unwrap();
+    assert_eq!(limiting.results.len(), 2);
+    assert_eq!(limiting.limiting, 1);
+    let expected = 20.0 / 31.998 * 3.0 / 5.0 * 44.009;
+    assert!((limiting.results[1].answer - expected).abs() < 1e-9);
+    assert!(matches!(registry.limiting_reagent("12 g [C3H8], 20 g [O2] : g [CO2]"), Err(ConversionError::InvalidReaction { .. })));
+}
+
+#[test]
+fn substances_that_are_divided_by_use_the_ratio_upside_down() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression(&format!("1 L / g [C3H8] : L / g [CO2] {COMBUSTION}")).unwrap();
+    let expected = 44.097 / 3.0 / 44.009;
+    assert!((result.answer - expected).abs() < 1e-9, "{}", result.answer);
+    assert_eq!((result.steps[1].top_value(), result.steps[1].bottom_value()), (1f64, 3f64));
+}
@@ -0,0 +1,63 @@
+mod common;
+
+use unit_conversions::Registry;
+use common::load_registry;
+
+fn close(answer: f64, expected: f64) -> bool {
+    (answer - expected).abs() < 1e-9
+}
+
+#[test]
+fn offsets_are_applied_to_absolute_temperatures() {
+    let mut registry = load_registry();
+    for (expression, expected) in [
+        ("100 celsius : fahrenheit", 212f64),
+        ("-40 °F : °C", -40f64),
+        ("300 kelvin : celsius", 26.85),
+        ("0 fahrenheit : rankine", 459.67),
+        ("32 degF : K", 273.15),
+    ] {
+        let answer = registry.convert_expression(expression).unwrap().answer;
+        assert!(close(answer, expected), "{expression} gave {answer}");
+    }
+}
+
+#[test]
+fn offsets_are_inverted_along_the_path() {
+    let mut registry = load_registry();
+    let there = registry.convert(37f64, "celsius", "fahrenheit").unwrap().answer;
+    let back = registry.convert(there, "fahrenheit", "celsius").unwrap().answer;
+    assert!(close(back, 37f64), "{back}");
+}
+
+#[test]
+fn scales_with_an_offset_are_defined_by_two_points() {
+    let mut registry = Registry::new();
+    for name in ["kelvin", "celsius"] {
+        registry.define_unit(name).unwrap();
+    }
+    registry.define_conversion("0 celsius = 273.15 kelvin, 100 celsius = 373.15 kelvin").unwrap();
+    assert!(close(registry.convert(20f64, "celsius", "kelvin").unwrap().answer, 293.15));
+}
+
+#[test]
+fn intervals_are_converted_without_the_offset() {
+    let mut registry = load_registry();
+    let absolute = registry.convert_expression("10 celsius : fahrenheit").unwrap();
+    assert!(close(absolute.answer, 50f64), "{}", absolute.answer);
+    for expression in ["10 delta celsius : delta fahrenheit", "10 Δ°C : Δ°F"] {
+        let interval = registry.convert_expression(expression).unwrap();
+        assert!(interval.query.interval, "{expression}");
+        assert!(close(interval.answer, 18f64), "{expression} gave {}", interval.answer);
+        assert_eq!(registry.answer_to_string(&interval), "20 Δfahrenheit");
+    }
+}
+
+#[test]
+fn temperatures_inside_compound_units_are_intervals() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("1.000 m / kelvin : m / fahrenheit").unwrap();
+    assert!(close(result.answer, 1f64 / 1.8), "{}", result.answer);
+    let result = registry.convert_expression("1.00 celsius * m : fahrenheit * m").unwrap();
+    assert!(close(result.answer, 1.8), "{}", result.answer);
+}
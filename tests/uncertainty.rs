@@ -0,0 +1,53 @@
+mod common;
+
+use unit_conversions::significant_figures;
+use common::load_registry;
+
+#[test]
+fn both_ways_of_writing_an_uncertainty_are_read() {
+    let mut registry = load_registry();
+    for expression in ["12.3 ± 0.2 g : kg", "12.3 +- 0.2 g : kg", "12.3±0.2 g : kg"] {
+        let result = registry.convert_expression(expression).unwrap();
+        assert_eq!(result.query.uncertainty, Some(0.2), "{expression}");
+        assert_eq!(registry.answer_to_string(&result), "0.0123 ± 0.0002 kilograms");
+    }
+}
+
+#[test]
+fn exact_factors_only_scale_the_uncertainty() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("2.00 ± 0.05 m / s : km / hr").unwrap();
+    assert!((result.uncertainty.unwrap() - 0.18).abs() < 1e-12);
+    assert_eq!(registry.answer_to_string(&result), "7.20 ± 0.18 kilometers / hours");
+}
+
+#[test]
+fn molar_masses_add_their_own_uncertainty() {
+    let mut registry = load_registry();
+    registry.define_element("Xx, Examplium = 10.0 ± 0.5").unwrap();
+    let result = registry.convert_expression("20 ± 0 g [Xx] : mol").unwrap();
+    // a 5% uncertain molar mass makes the answer 5% uncertain
+    assert!((result.uncertainty.unwrap() - 0.1).abs() < 1e-12);
+    assert_eq!(registry.answer_to_string(&result), "2.00 ± 0.10 moles [Xx]");
+}
+
+#[test]
+fn uncertainties_of_several_values_combine() {
+    let mut registry = load_registry();
+    let result = registry.convert_expression("3 ± 0.3 m / 4 ± 0.4 s : m / s").unwrap();
+    let expected = 0.75 * (0.1f64.powi(2) * 2f64).sqrt();
+    assert!((result.uncertainty.unwrap() - expected).abs() < 1e-12);
+}
+
+#[test]
+fn a_missing_uncertainty_is_an_error() {
+    let mut registry = load_registry();
+    assert!(registry.convert_expression("12.3 ± g : kg").is_err());
+}
+
+#[test]
+fn values_are_rounded_to_the_last_digit_of_their_uncertainty() {
+    for (value, uncertainty, text) in [(0.68276, 0.01110, "0.683 ± 0.011"), (12.3, 0.2, "12.3 ± 0.2"), (6.0221e23, 6.02e22, "6.0e23 ± 6e22")] {
+        assert_eq!(significant_figures::format_with_uncertainty(value, uncertainty), text);
+    }
+}